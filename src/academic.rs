@@ -0,0 +1,212 @@
+//! The academic calendar, which the API doesn't provide: the dates of the
+//! lessons and evaluation seasons of each term, along with holidays.
+//! Timetables are laid out against it (see `calendar`, `ics` and `rooms`).
+
+use crate::models::{Period, Season, Weekday};
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, Days, NaiveDate, TimeDelta};
+
+/// The dates of the `period` of a school `year`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Term {
+    pub year: u32,
+    pub period: Period,
+    /// First and last day of lessons, both inclusive
+    pub lessons: (NaiveDate, NaiveDate),
+    /// First and last day of each evaluation season, both inclusive
+    pub seasons: BTreeMap<Season, (NaiveDate, NaiveDate)>,
+}
+
+/// When terms, their evaluation seasons and holidays take place.
+///
+/// Terms of different periods may overlap (eg. a year and its semesters).
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AcademicCalendar {
+    pub terms: Vec<Term>,
+    /// Dates without lessons or evaluations
+    pub holidays: BTreeSet<NaiveDate>,
+}
+
+impl Term {
+    #[must_use]
+    pub fn new(
+        year: u32,
+        period: Period,
+        first_day: NaiveDate,
+        last_day: NaiveDate,
+    ) -> Term {
+        Term {
+            year,
+            period,
+            lessons: (first_day, last_day),
+            seasons: BTreeMap::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_season(
+        mut self,
+        season: Season,
+        first_day: NaiveDate,
+        last_day: NaiveDate,
+    ) -> Term {
+        self.seasons.insert(season, (first_day, last_day));
+        self
+    }
+
+    /// The first day of lessons or evaluations
+    #[must_use]
+    pub fn start(&self) -> NaiveDate {
+        self.seasons
+            .values()
+            .map(|(first_day, _)| *first_day)
+            .fold(self.lessons.0, NaiveDate::min)
+    }
+
+    /// The last day of lessons or evaluations
+    #[must_use]
+    pub fn end(&self) -> NaiveDate {
+        self.seasons
+            .values()
+            .map(|(_, last_day)| *last_day)
+            .fold(self.lessons.1, NaiveDate::max)
+    }
+
+    /// The evaluation season taking place on `date`, if any
+    #[must_use]
+    pub fn season_on(&self, date: NaiveDate) -> Option<Season> {
+        self.seasons
+            .iter()
+            .find(|(_, (first_day, last_day))| {
+                (*first_day..=*last_day).contains(&date)
+            })
+            .map(|(season, _)| *season)
+    }
+}
+
+impl AcademicCalendar {
+    #[must_use]
+    pub fn new() -> AcademicCalendar {
+        AcademicCalendar::default()
+    }
+
+    /// Adds `term`, replacing any other of the same year and period
+    #[must_use]
+    pub fn with_term(mut self, term: Term) -> AcademicCalendar {
+        self.terms.retain(|other| {
+            (other.year, other.period) != (term.year, term.period)
+        });
+        self.terms.push(term);
+        self
+    }
+
+    #[must_use]
+    pub fn with_holidays<I: IntoIterator<Item = NaiveDate>>(
+        mut self,
+        holidays: I,
+    ) -> AcademicCalendar {
+        self.holidays.extend(holidays);
+        self
+    }
+
+    #[must_use]
+    pub fn term(&self, year: u32, period: Period) -> Option<&Term> {
+        self.terms
+            .iter()
+            .find(|term| term.year == year && term.period == period)
+    }
+
+    /// The terms taking place on `date`, lessons or evaluations alike
+    #[must_use]
+    pub fn terms_on(&self, date: NaiveDate) -> Vec<&Term> {
+        self.terms
+            .iter()
+            .filter(|term| (term.start()..=term.end()).contains(&date))
+            .collect()
+    }
+
+    #[must_use]
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.holidays.contains(&date)
+    }
+
+    /// Whether there are lessons of the `period` of `year` on `date`
+    #[must_use]
+    pub fn is_lesson_day(
+        &self,
+        year: u32,
+        period: Period,
+        date: NaiveDate,
+    ) -> bool {
+        self.term(year, period).is_some_and(|term| {
+            (term.lessons.0..=term.lessons.1).contains(&date)
+                && !self.is_holiday(date)
+        })
+    }
+
+    /// Every `weekday` with lessons of the `period` of `year`
+    #[must_use]
+    pub fn lesson_days(
+        &self,
+        year: u32,
+        period: Period,
+        weekday: Weekday,
+    ) -> Vec<NaiveDate> {
+        let Some(term) = self.term(year, period) else {
+            return vec![];
+        };
+        let (first_day, last_day) = term.lessons;
+        weekly_dates(weekday, first_day, last_day)
+            .filter(|date| !self.is_holiday(*date))
+            .collect()
+    }
+
+    /// The Mondays of the weeks with lessons of the `period` of `year`.
+    /// Weeks in which every weekday is a holiday (eg. breaks) don't count.
+    #[must_use]
+    pub fn lesson_weeks(&self, year: u32, period: Period) -> Vec<NaiveDate> {
+        let Some(term) = self.term(year, period) else {
+            return vec![];
+        };
+        let (first_day, last_day) = term.lessons;
+        let first_monday = first_day.week(chrono::Weekday::Mon).first_day();
+        first_monday
+            .iter_weeks()
+            .take_while(|monday| *monday <= last_day)
+            .filter(|monday| {
+                (0..5)
+                    .filter_map(|offset| {
+                        monday.checked_add_days(Days::new(offset))
+                    })
+                    .any(|date| {
+                        (first_day..=last_day).contains(&date)
+                            && !self.is_holiday(date)
+                    })
+            })
+            .collect()
+    }
+
+    /// The evaluation season taking place on `date`, along with its term
+    #[must_use]
+    pub fn season_on(&self, date: NaiveDate) -> Option<(&Term, Season)> {
+        self.terms
+            .iter()
+            .find_map(|term| term.season_on(date).map(|season| (term, season)))
+    }
+}
+
+/// Every `weekday` from `from` to `to`, both inclusive
+pub(crate) fn weekly_dates(
+    weekday: Weekday,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> impl Iterator<Item = NaiveDate> {
+    let weekday = chrono::Weekday::from(weekday);
+    let offset = (7 + weekday.num_days_from_monday()
+        - from.weekday().num_days_from_monday())
+        % 7;
+    let first = from + TimeDelta::days(i64::from(offset));
+    first.iter_weeks().take_while(move |date| *date <= to)
+}
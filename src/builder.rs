@@ -0,0 +1,225 @@
+use crate::network::endpoints::{
+    AuthenticatedSupernova, BaseSupernova, TokenInvalidation,
+};
+use crate::network::http::Connection;
+use crate::network::retry::RetryPolicy;
+use crate::network::transport::{
+    HyperTransport, PoolConfig, Timeouts, Transport,
+};
+use crate::session::TokenStore;
+use crate::{AuthToken, RequestConfig, Supernova};
+
+use std::env;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+pub(crate) const DEFAULT_UPSTREAM: &str = "https://supernova.nunl.pt";
+const UPSTREAM_ENV_VAR: &str = "SUPERNOVA_UPSTREAM";
+
+/// Configures and instantiates `Supernova` clients.
+///
+/// Clients talk to the official upstream unless told otherwise.
+/// Since the authentication token is sent to whichever upstream is set, a
+/// different one has to be explicitly requested, either with `upstream` or by
+/// opting into the `SUPERNOVA_UPSTREAM` environment variable with
+/// `upstream_from_env`.
+pub struct SupernovaBuilder {
+    upstream: String,
+    transport: Option<Arc<dyn Transport>>,
+    pool: PoolConfig,
+    timeouts: Timeouts,
+    retry_policy: RetryPolicy,
+    user_agent: String,
+    request_config: RequestConfig,
+    send_client_meta: bool,
+    token_invalidation: TokenInvalidation,
+    token_store: Option<Arc<dyn TokenStore>>,
+    concurrent_fetches: usize,
+}
+
+impl Default for SupernovaBuilder {
+    fn default() -> Self {
+        SupernovaBuilder {
+            upstream: DEFAULT_UPSTREAM.to_string(),
+            transport: None,
+            pool: PoolConfig::default(),
+            timeouts: Timeouts {
+                connect: Some(Duration::from_secs(10)),
+                read: Some(Duration::from_secs(30)),
+                total: None,
+            },
+            retry_policy: RetryPolicy::default(),
+            user_agent: format!("iron_planet/{}", env!("CARGO_PKG_VERSION")),
+            request_config: RequestConfig::default(),
+            send_client_meta: true,
+            token_invalidation: TokenInvalidation::default(),
+            token_store: None,
+            concurrent_fetches: 8,
+        }
+    }
+}
+
+impl SupernovaBuilder {
+    #[must_use]
+    pub fn new() -> SupernovaBuilder {
+        SupernovaBuilder::default()
+    }
+
+    /// Base URL of the upstream server (eg. `https://supernova.nunl.pt`)
+    #[must_use]
+    pub fn upstream(mut self, url: &str) -> SupernovaBuilder {
+        self.upstream = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Uses the upstream in the `SUPERNOVA_UPSTREAM` environment variable,
+    /// if it is set.
+    ///
+    /// Whoever controls the environment gets to receive the session tokens,
+    /// hence this being opt-in.
+    #[must_use]
+    pub fn upstream_from_env(self) -> SupernovaBuilder {
+        if let Ok(url) = env::var(UPSTREAM_ENV_VAR) {
+            self.upstream(&url)
+        } else {
+            self
+        }
+    }
+
+    /// Communicate through `transport` instead of the default
+    /// `HyperTransport`. Overrides the pool configuration.
+    #[must_use]
+    pub fn transport(
+        mut self,
+        transport: Arc<dyn Transport>,
+    ) -> SupernovaBuilder {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// Tuning of the connection pool of the default transport
+    #[must_use]
+    pub fn pool(mut self, config: PoolConfig) -> SupernovaBuilder {
+        self.pool = config;
+        self
+    }
+
+    /// Maximum duration of each request.
+    /// Shorthand for setting the `total` of the `timeouts`.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> SupernovaBuilder {
+        self.timeouts.total = Some(timeout);
+        self
+    }
+
+    /// Default timeouts of every request, which can be overridden through
+    /// `RequestConfig::timeouts`. These default to 10 seconds to connect and
+    /// 30 seconds of wait for data, without an overall limit.
+    ///
+    /// Timeouts require the Tokio runtime to have its time driver enabled.
+    #[must_use]
+    pub fn timeouts(mut self, timeouts: Timeouts) -> SupernovaBuilder {
+        self.timeouts = timeouts;
+        self
+    }
+
+    /// How failed data fetches are retried.
+    /// Retries are delayed through the Tokio runtime's time driver.
+    #[must_use]
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> SupernovaBuilder {
+        self.retry_policy = policy;
+        self
+    }
+
+    /// `User-Agent` sent along every request
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: &str) -> SupernovaBuilder {
+        self.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Configuration used by requests which aren't explicitly given one,
+    /// such as those made when following relations between models.
+    #[must_use]
+    pub fn request_config(mut self, config: RequestConfig) -> SupernovaBuilder {
+        self.request_config = config;
+        self
+    }
+
+    /// How many requests are made at once by operations needing many models
+    /// fetched, such as `Supernova::room_occupancy`. Defaults to 8.
+    #[must_use]
+    pub fn concurrent_fetches(mut self, limit: usize) -> SupernovaBuilder {
+        self.concurrent_fetches = limit.max(1);
+        self
+    }
+
+    /// Whether to describe this system (name, hostname, versions) on login
+    #[must_use]
+    pub fn send_client_meta(mut self, send: bool) -> SupernovaBuilder {
+        self.send_client_meta = send;
+        self
+    }
+
+    /// Whether to forget the authentication token once the server rejects it
+    /// (eg. because it expired), after which the client is unauthenticated.
+    #[must_use]
+    pub fn clear_invalid_tokens(mut self, clear: bool) -> SupernovaBuilder {
+        self.token_invalidation.clear = clear;
+        self
+    }
+
+    /// Calls `callback` with the authentication token whenever the server
+    /// rejects it, as a cue to log in again.
+    #[must_use]
+    pub fn on_token_invalidated<F>(mut self, callback: F) -> SupernovaBuilder
+    where
+        F: Fn(&AuthToken) + Send + Sync + 'static,
+    {
+        self.token_invalidation.callback = Some(Arc::new(callback));
+        self
+    }
+
+    /// Persists the authentication token in `store` upon login and forgets it
+    /// upon logout, allowing `Supernova::restore_session` to resume sessions.
+    #[must_use]
+    pub fn token_store(
+        mut self,
+        store: Arc<dyn TokenStore>,
+    ) -> SupernovaBuilder {
+        self.token_store = Some(store);
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> Arc<Supernova> {
+        Arc::new(self.build_unwrapped())
+    }
+
+    pub(crate) fn build_unwrapped(self) -> Supernova {
+        let transport = self
+            .transport
+            .unwrap_or_else(|| Arc::new(HyperTransport::new(&self.pool)));
+        let connection = Arc::new(Connection {
+            transport,
+            upstream: self.upstream,
+            user_agent: self.user_agent,
+            timeouts: self.timeouts,
+            retry_policy: Arc::new(self.retry_policy),
+        });
+
+        Supernova {
+            base: BaseSupernova::new(connection.clone()),
+            authenticated: AuthenticatedSupernova::new(
+                connection.clone(),
+                self.token_invalidation,
+                self.token_store,
+            ),
+            cache: RwLock::default(),
+            connection,
+            request_config: self.request_config,
+            send_client_meta: self.send_client_meta,
+            concurrent_fetches: self.concurrent_fetches,
+        }
+    }
+}
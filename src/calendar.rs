@@ -0,0 +1,242 @@
+//! Dated occurrences of lessons and group schedulings, such as those in
+//! [`Supernova::personal_calendar`].
+
+use crate::academic::{weekly_dates, AcademicCalendar};
+use crate::errors::Error;
+use crate::keys::{GroupKey, ShiftKey};
+use crate::models::{
+    ClassShift, ClassShiftInstance, GroupScheduling, GroupSchedulingOnce,
+    GroupSchedulingPeriodic, Weekday,
+};
+use crate::{RequestConfig, Supernova, CAMPUS_TIMEZONE};
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+
+/// Something taking place at a concrete date and time.
+/// Times are in the local time of the campus.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub source: OccurrenceSource,
+}
+
+/// What an occurrence is an occurrence of
+#[derive(Debug, Clone, PartialEq)]
+pub enum OccurrenceSource {
+    /// A lesson of a shift
+    Shift {
+        shift: ClassShift,
+        instance: ClassShiftInstance,
+    },
+    /// A scheduled activity of a group
+    Group {
+        group: GroupKey,
+        group_name: String,
+        title: Option<String>,
+    },
+}
+
+/// Chronological, with simultaneous occurrences of different sources being
+/// incomparable
+impl PartialOrd for Occurrence {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.start, self.end).cmp(&(other.start, other.end)) {
+            Ordering::Equal if self.source != other.source => None,
+            ordering => Some(ordering),
+        }
+    }
+}
+
+impl ClassShiftInstance {
+    #[must_use]
+    pub fn start_time(&self) -> NaiveTime {
+        NaiveTime::MIN + TimeDelta::minutes(i64::from(self.start))
+    }
+
+    /// The (start, end) of every lesson starting from `from` to `to`,
+    /// both inclusive
+    #[must_use]
+    pub fn occurrences(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        weekly_dates(self.weekday, from, to)
+            .map(|date| self.lesson_on(date))
+            .collect()
+    }
+
+    /// The (start, end) of the lesson on `date`
+    fn lesson_on(&self, date: NaiveDate) -> (NaiveDateTime, NaiveDateTime) {
+        let start = date.and_time(self.start_time());
+        (start, start + TimeDelta::minutes(i64::from(self.duration)))
+    }
+}
+
+impl GroupSchedulingOnce {
+    /// The (start, end) of this, if it takes place from `from` to `to`,
+    /// both inclusive
+    #[must_use]
+    pub fn occurrence(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Option<(NaiveDateTime, NaiveDateTime)> {
+        let start = self.datetime.with_timezone(&CAMPUS_TIMEZONE).naive_local();
+        if self.revoked || start.date() < from || start.date() > to {
+            return None;
+        }
+        Some((start, start + TimeDelta::minutes(i64::from(self.duration))))
+    }
+}
+
+impl GroupSchedulingPeriodic {
+    /// The (start, end) of every repetition starting from `from` to `to`,
+    /// both inclusive
+    #[must_use]
+    pub fn occurrences(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        if self.revoked {
+            return vec![];
+        }
+        let duration = TimeDelta::minutes(i64::from(self.duration));
+        weekly_dates(
+            self.weekday,
+            from.max(self.start_date),
+            to.min(self.end_date),
+        )
+        .map(|date| {
+            let start = date.and_time(self.time);
+            (start, start + duration)
+        })
+        .collect()
+    }
+}
+
+impl GroupScheduling {
+    /// The (start, end) of every occurrence starting from `from` to `to`,
+    /// both inclusive
+    #[must_use]
+    pub fn occurrences(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Vec<(NaiveDateTime, NaiveDateTime)> {
+        match self {
+            GroupScheduling::Once(scheduling) => {
+                scheduling.occurrence(from, to).into_iter().collect()
+            }
+            GroupScheduling::Periodic(scheduling) => {
+                scheduling.occurrences(from, to)
+            }
+        }
+    }
+}
+
+impl Supernova {
+    /// Every lesson of the shifts of the authenticated user's students,
+    /// along with the scheduled activities of the groups they follow,
+    /// from `from` to `to` (both inclusive), sorted chronologically.
+    ///
+    /// Lessons only take place within the term of their class instance,
+    /// save for holidays, as set in `calendar`.
+    /// Shifts of class instances in a term the calendar doesn't know of, or
+    /// of class instances the students aren't enrolled in, are left out.
+    pub async fn personal_calendar(
+        self: &Arc<Supernova>,
+        from: NaiveDate,
+        to: NaiveDate,
+        calendar: &AcademicCalendar,
+        conf: &RequestConfig,
+    ) -> Result<Vec<Occurrence>, Error> {
+        let me = self.me(conf).await?;
+        let mut occurrences = vec![];
+
+        let mut seen_shifts = HashSet::new();
+        for student_ref in &me.profile.students {
+            let student =
+                self.get_student(student_ref.identifier, conf).await?;
+            // Shifts don't necessarily know their class instance, but the
+            // instances the student is enrolled in know their shifts
+            let mut terms: HashMap<ShiftKey, _> = HashMap::new();
+            for enrollment in student.get_enrollments().await? {
+                let class_inst = enrollment.get_class_instance().await?;
+                for shift_ref in &class_inst.shifts {
+                    terms.insert(
+                        shift_ref.identifier,
+                        (class_inst.year, class_inst.period),
+                    );
+                }
+            }
+            for shift_ref in &student.shifts {
+                let Some(&(year, period)) = terms.get(&shift_ref.identifier)
+                else {
+                    continue;
+                };
+                if !seen_shifts.insert(shift_ref.identifier) {
+                    continue;
+                }
+                let shift = self.get_shift(shift_ref.identifier, conf).await?;
+                for instance in &shift.instances {
+                    let lessons = calendar
+                        .lesson_days(year, period, instance.weekday)
+                        .into_iter()
+                        .filter(|date| (from..=to).contains(date))
+                        .map(|date| instance.lesson_on(date));
+                    occurrences.extend(lessons.map(|(start, end)| {
+                        Occurrence {
+                            start,
+                            end,
+                            source: OccurrenceSource::Shift {
+                                shift: shift.clone(),
+                                instance: instance.clone(),
+                            },
+                        }
+                    }));
+                }
+            }
+        }
+
+        for group_ref in &me.profile.groups {
+            let (group_name, schedulings) = {
+                let group =
+                    self.get_group_with(group_ref.identifier, conf).await?;
+                // Groups fetched by themselves come with their schedulings
+                let schedulings =
+                    group.schedulings.get().cloned().unwrap_or_default();
+                (group.name, schedulings)
+            };
+            for scheduling in &schedulings {
+                let title = match scheduling {
+                    GroupScheduling::Once(scheduling) => &scheduling.title,
+                    GroupScheduling::Periodic(scheduling) => &scheduling.title,
+                };
+                occurrences.extend(
+                    scheduling.occurrences(from, to).into_iter().map(
+                        |(start, end)| Occurrence {
+                            start,
+                            end,
+                            source: OccurrenceSource::Group {
+                                group: group_ref.identifier,
+                                group_name: group_name.clone(),
+                                title: title.clone(),
+                            },
+                        },
+                    ),
+                );
+            }
+        }
+
+        occurrences
+            .sort_by_key(|occurrence| (occurrence.start, occurrence.end));
+        Ok(occurrences)
+    }
+}
@@ -1,7 +1,7 @@
 use crate::errors::Error;
 use crate::keys::*;
 use crate::models::*;
-use crate::{RequestConfig, Supernova};
+use crate::Supernova;
 
 use std::fmt;
 use std::marker::PhantomData;
@@ -21,7 +21,8 @@ pub(crate) trait CoersibleEntity<I> {
 #[derive(Clone)]
 pub(crate) struct ObjRef<T: CoersibleEntity<I>, I> {
     pub(crate) identifier: I,
-    _type: PhantomData<T>,
+    // Does not hold a T, and so is Send and Sync regardless of it
+    _type: PhantomData<fn() -> T>,
     // obj: Option<T>,// OnceCell
     client: Arc<Supernova>,
 }
@@ -40,6 +41,11 @@ impl<T: CoersibleEntity<I>, I> ObjRef<T, I> {
     pub(crate) async fn coerce(&self) -> Result<T, Error> {
         T::coerce(&self.identifier, self.client.clone()).await
     }
+
+    /// The client this would be obtained through
+    pub(crate) fn client(&self) -> &Arc<Supernova> {
+        &self.client
+    }
 }
 
 impl<T: CoersibleEntity<I>, I: fmt::Debug> fmt::Debug for ObjRef<T, I> {
@@ -58,7 +64,7 @@ impl CoersibleEntity<DepartmentKey> for Department {
         id: &DepartmentKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_department(*id, &RequestConfig::default()).await
+        client.get_department(*id, &client.request_config).await
     }
 }
 
@@ -70,7 +76,7 @@ impl CoersibleEntity<BuildingKey> for Building {
         id: &BuildingKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_building(*id, &RequestConfig::default()).await
+        client.get_building(*id, &client.request_config).await
     }
 }
 
@@ -82,7 +88,7 @@ impl CoersibleEntity<PlaceKey> for Place {
         id: &PlaceKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_place(*id, &RequestConfig::default()).await
+        client.get_place(*id, &client.request_config).await
     }
 }
 
@@ -94,7 +100,7 @@ impl CoersibleEntity<CourseKey> for Course {
         id: &CourseKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_course(*id, &RequestConfig::default()).await
+        client.get_course(*id, &client.request_config).await
     }
 }
 
@@ -106,7 +112,7 @@ impl CoersibleEntity<ClassKey> for Class {
         id: &ClassKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_class(*id, &RequestConfig::default()).await
+        client.get_class(*id, &client.request_config).await
     }
 }
 
@@ -114,17 +120,43 @@ impl ObjRef<Class, ClassKey> {}
 
 #[async_trait]
 impl CoersibleEntity<ClassInstanceKey> for ClassInstance {
+    async fn coerce(
+        id: &ClassInstanceKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client.get_class_instance(*id, &client.request_config).await
+    }
+}
+
+impl ObjRef<ClassInstance, ClassInstanceKey> {}
+
+#[async_trait]
+impl CoersibleEntity<ClassInstanceKey> for ClassInstanceFiles {
     async fn coerce(
         id: &ClassInstanceKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
         client
-            .get_class_instance(*id, &RequestConfig::default())
+            .get_class_instance_files(*id, &client.request_config)
             .await
     }
 }
 
-impl ObjRef<ClassInstance, ClassInstanceKey> {}
+impl ObjRef<ClassInstanceFiles, ClassInstanceKey> {}
+
+#[async_trait]
+impl CoersibleEntity<ClassInstanceKey> for Vec<ClassEvent> {
+    async fn coerce(
+        id: &ClassInstanceKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client
+            .get_class_instance_events(*id, &client.request_config)
+            .await
+    }
+}
+
+impl ObjRef<Vec<ClassEvent>, ClassInstanceKey> {}
 
 #[async_trait]
 impl CoersibleEntity<StudentKey> for Student {
@@ -132,7 +164,7 @@ impl CoersibleEntity<StudentKey> for Student {
         id: &StudentKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_student(*id, &RequestConfig::default()).await
+        client.get_student(*id, &client.request_config).await
     }
 }
 
@@ -144,7 +176,7 @@ impl CoersibleEntity<TeacherKey> for Teacher {
         id: &TeacherKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_teacher(*id, &RequestConfig::default()).await
+        client.get_teacher(*id, &client.request_config).await
     }
 }
 
@@ -156,7 +188,7 @@ impl CoersibleEntity<EnrollmentKey> for Enrollment {
         id: &EnrollmentKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_enrollment(*id, &RequestConfig::default()).await
+        client.get_enrollment(*id, &client.request_config).await
     }
 }
 
@@ -168,7 +200,7 @@ impl CoersibleEntity<ShiftKey> for ClassShift {
         id: &ShiftKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_shift(*id, &RequestConfig::default()).await
+        client.get_shift(*id, &client.request_config).await
     }
 }
 
@@ -179,11 +211,10 @@ impl ObjRef<ClassShift, ShiftKey> {}
 #[async_trait]
 impl CoersibleEntity<UserKey> for User {
     async fn coerce(
-        _id: &UserKey,
-        _client: Arc<Supernova>,
+        id: &UserKey,
+        client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        todo!()
-        // client.get_user(*id, &RequestConfig::default())).await
+        client.get_user(*id, &client.request_config).await
     }
 }
 
@@ -191,14 +222,25 @@ impl ObjRef<User, UserKey> {}
 
 // ------------ Groups ------------
 
+#[async_trait]
+impl CoersibleEntity<GroupKey> for Group {
+    async fn coerce(
+        id: &GroupKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client.get_group(*id).await
+    }
+}
+
+impl ObjRef<Group, GroupKey> {}
+
 #[async_trait]
 impl CoersibleEntity<EventKey> for Event {
     async fn coerce(
-        _id: &EventKey,
-        _client: Arc<Supernova>,
+        id: &EventKey,
+        client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        todo!()
-        // client.get_event(*id, &RequestConfig::default()).await
+        client.get_event(*id, &client.request_config).await
     }
 }
 
@@ -210,7 +252,7 @@ impl CoersibleEntity<EventsPageKey> for Option<Arc<EventsPage>> {
         id: &EventsPageKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_events_page(*id, &RequestConfig::default()).await
+        client.get_events_page(*id, &client.request_config).await
     }
 }
 // ------------ News --------------
@@ -221,6 +263,74 @@ impl CoersibleEntity<NewsPageKey> for Option<Arc<NewsPage>> {
         id: &NewsPageKey,
         client: Arc<Supernova>,
     ) -> Result<Self, Error> {
-        client.get_news_page(*id, &RequestConfig::default()).await
+        client.get_news_page(*id, &client.request_config).await
+    }
+}
+
+// ------------ Learning --------------
+
+#[async_trait]
+impl CoersibleEntity<LearningAreaKey> for LearningArea {
+    async fn coerce(
+        id: &LearningAreaKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client.get_learning_area(*id, &client.request_config).await
+    }
+}
+
+impl ObjRef<LearningArea, LearningAreaKey> {}
+
+#[async_trait]
+impl CoersibleEntity<LearningSubareaKey> for LearningSubarea {
+    async fn coerce(
+        id: &LearningSubareaKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client
+            .get_learning_subarea(*id, &client.request_config)
+            .await
+    }
+}
+
+impl ObjRef<LearningSubarea, LearningSubareaKey> {}
+
+#[async_trait]
+impl CoersibleEntity<LearningSectionKey> for LearningSection {
+    async fn coerce(
+        id: &LearningSectionKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client
+            .get_learning_section(*id, &client.request_config)
+            .await
+    }
+}
+
+impl ObjRef<LearningSection, LearningSectionKey> {}
+
+#[async_trait]
+impl CoersibleEntity<QuestionKey> for Question {
+    async fn coerce(
+        id: &QuestionKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client.get_question(*id, &client.request_config).await
     }
 }
+
+impl ObjRef<Question, QuestionKey> {}
+
+// ------------ Services --------------
+
+#[async_trait]
+impl CoersibleEntity<ServiceKey> for Service {
+    async fn coerce(
+        id: &ServiceKey,
+        client: Arc<Supernova>,
+    ) -> Result<Self, Error> {
+        client.get_service(*id, &client.request_config).await
+    }
+}
+
+impl ObjRef<Service, ServiceKey> {}
@@ -0,0 +1,77 @@
+use crate::errors::Error;
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use sha1_smol::Sha1;
+
+/// How far along a download is
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct DownloadProgress {
+    /// Bytes received so far
+    pub received: u64,
+    /// Expected size of the file, in bytes
+    pub total: u64,
+}
+
+/// A download in progress, written next to its destination
+/// and hashed as it arrives
+pub(crate) struct PartialFile {
+    file: fs::File,
+    path: PathBuf,
+    destination: PathBuf,
+    hasher: Sha1,
+    received: u64,
+}
+
+impl PartialFile {
+    pub(crate) fn create(destination: &Path) -> Result<PartialFile, Error> {
+        if let Some(dir) = destination.parent() {
+            fs::create_dir_all(dir).map_err(Error::Io)?;
+        }
+        let mut path = destination.as_os_str().to_owned();
+        path.push(".part");
+        let path = PathBuf::from(path);
+        let file = fs::File::create(&path).map_err(Error::Io)?;
+        Ok(PartialFile {
+            file,
+            path,
+            destination: destination.to_path_buf(),
+            hasher: Sha1::new(),
+            received: 0,
+        })
+    }
+
+    /// Appends `chunk`, returning how many bytes were received so far
+    pub(crate) fn write(&mut self, chunk: &[u8]) -> Result<u64, Error> {
+        self.file.write_all(chunk).map_err(Error::Io)?;
+        self.hasher.update(chunk);
+        self.received += chunk.len() as u64;
+        Ok(self.received)
+    }
+
+    /// Moves the download into its destination, given that its SHA-1 hash
+    /// is `expected`. Otherwise, it is discarded.
+    pub(crate) fn finish(self, expected: &str) -> Result<(), Error> {
+        let actual = self.hasher.digest().to_string();
+        if !actual.eq_ignore_ascii_case(expected) {
+            self.discard();
+            return Err(Error::HashMismatch(expected.to_string(), actual));
+        }
+        let result = self
+            .file
+            .sync_all()
+            .and_then(|()| fs::rename(&self.path, &self.destination));
+        if let Err(err) = result {
+            self.discard();
+            return Err(Error::Io(err));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn discard(self) {
+        drop(self.file);
+        let _ = fs::remove_file(&self.path);
+    }
+}
@@ -1,31 +1,132 @@
+use crate::network::endpoints::Endpoint;
+
+use std::error::Error as StdError;
+use std::fmt;
+
 use thiserror::Error;
 
+/// Bodies are only so long before they stop being a useful message
+const MAX_MESSAGE_LENGTH: usize = 500;
+
 #[derive(Error, Debug)]
 pub enum Error {
     // TODO get rid of this one
     #[error("Something possibly bad failed not to happen")]
     Generic,
     // -----------
-    #[error("An error occurred on the server")]
-    Server,
+    #[error("An error occurred on the server ({0})")]
+    Server(HttpError),
     #[error("Failed to decode the received message")]
     Decode,
     #[error("Unable to serialize data:\n{0}")]
-    Serialization(serde_json::Error),
+    Serialization(#[source] serde_json::Error),
     #[error("Unable to deserialize data:\n{0}")]
-    Deserialization(serde_json::Error),
+    Deserialization(#[source] serde_json::Error),
     #[error("Received a message that didn't match the expected format:\n{0}")]
-    Parsing(serde_json::Error, String),
-    #[error("A resource is missing on the server")]
-    ResourceMissing,
+    Parsing(#[source] serde_json::Error, String),
+    #[error("A resource is missing on the server ({0})")]
+    ResourceMissing(HttpError),
+    #[error("The server redirected the request elsewhere ({0})")]
+    Redirection(HttpError),
     #[error("Attempted to access a protected resource without providing credentials")]
     MissingAuthentication,
+    /// The credentials were rejected (eg. the token expired)
     #[error("Failed to authenticate to the server")]
-    Authentication,
-    #[error("Client-side error")]
-    Client,
+    Authentication(Option<HttpError>),
+    /// The credentials are valid, but don't grant access to the resource
+    #[error("Not allowed to access the resource ({0})")]
+    PermissionDenied(HttpError),
+    #[error("Client-side error ({0})")]
+    Client(HttpError),
     #[error("Network failure")]
-    Network,
+    Network(#[source] Option<Box<dyn StdError + Send + Sync>>),
+    #[error("The request took longer than allowed")]
+    Timeout,
     #[error("There was an error completing a request")]
     Request,
+    #[error("An I/O operation failed:\n{0}")]
+    Io(#[source] std::io::Error),
+    /// A downloaded file isn't what it was supposed to be
+    #[error(
+        "The downloaded file doesn't match its hash (expected {0}, got {1})"
+    )]
+    HashMismatch(String, String),
+}
+
+impl Error {
+    /// Whether the failure is transient, such that trying again might succeed
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Error::Network(_) | Error::Timeout => true,
+            Error::Server(err) => matches!(err.status, 502..=504),
+            Error::Client(err) => matches!(err.status, 408 | 429),
+            _ => false,
+        }
+    }
+
+    /// Whether the failure is due to missing or insufficient credentials
+    #[must_use]
+    pub fn is_auth(&self) -> bool {
+        matches!(
+            self,
+            Error::MissingAuthentication
+                | Error::Authentication(_)
+                | Error::PermissionDenied(_)
+        )
+    }
+
+    /// Details of the response, if the error is due to its status code
+    #[must_use]
+    pub fn http(&self) -> Option<&HttpError> {
+        match self {
+            Error::Authentication(Some(err))
+            | Error::Server(err)
+            | Error::ResourceMissing(err)
+            | Error::PermissionDenied(err)
+            | Error::Redirection(err)
+            | Error::Client(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// A response whose status code denotes an error
+#[derive(Debug, Clone)]
+pub struct HttpError {
+    pub status: u16,
+    /// The endpoint that was requested, unless the request was for a plain URL
+    pub endpoint: Option<Endpoint>,
+    pub url: String,
+    /// The `detail` message sent by the server, if any
+    pub detail: Option<String>,
+    pub body: Vec<u8>,
+}
+
+impl HttpError {
+    /// The explanation given by the server, which is either its `detail`
+    /// message or, lacking one, the body of the response.
+    #[must_use]
+    pub fn message(&self) -> Option<String> {
+        if self.detail.is_some() {
+            return self.detail.clone();
+        }
+        let body = String::from_utf8_lossy(&self.body);
+        let body = body.trim();
+        if body.is_empty() {
+            None
+        } else {
+            Some(body.chars().take(MAX_MESSAGE_LENGTH).collect())
+        }
+    }
+}
+
+impl fmt::Display for HttpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "HTTP {} from {}", self.status, self.url)?;
+        if let Some(message) = self.message() {
+            write!(f, ": {message}")?;
+        }
+        Ok(())
+    }
 }
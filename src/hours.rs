@@ -0,0 +1,181 @@
+//! Opening hours: whether something is open at a given time and when it
+//! next opens or closes, with holidays and date-specific exceptions taking
+//! precedence over the weekly periods.
+
+use crate::models::Weekday;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{
+    DateTime, Datelike, Days, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta,
+    TimeZone,
+};
+
+/// A weekly period throughout which something is open.
+/// Periods which end at or before their start run past midnight.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct OpeningPeriod {
+    pub weekday: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// When a service or place is open, made out of weekly periods
+/// along with the dates which do not follow them.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct OpeningHours {
+    pub weekly: Vec<OpeningPeriod>,
+    /// Dates with hours of their own (closed when empty).
+    /// These take precedence over both the weekly periods and the holidays.
+    pub exceptions: BTreeMap<NaiveDate, Vec<(NaiveTime, NaiveTime)>>,
+    /// Dates throughout which it is closed
+    pub holidays: BTreeSet<NaiveDate>,
+}
+
+/// A change between being open and closed
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Transition<Tz: TimeZone> {
+    pub at: DateTime<Tz>,
+    /// Whether this is an opening or a closing
+    pub opens: bool,
+}
+
+impl OpeningHours {
+    #[must_use]
+    pub fn weekly(periods: Vec<OpeningPeriod>) -> OpeningHours {
+        OpeningHours {
+            weekly: periods,
+            ..OpeningHours::default()
+        }
+    }
+
+    /// Replaces the hours of `date` with `periods`
+    #[must_use]
+    pub fn with_exception(
+        mut self,
+        date: NaiveDate,
+        periods: Vec<(NaiveTime, NaiveTime)>,
+    ) -> OpeningHours {
+        self.exceptions.insert(date, periods);
+        self
+    }
+
+    #[must_use]
+    pub fn with_holidays<I: IntoIterator<Item = NaiveDate>>(
+        mut self,
+        dates: I,
+    ) -> OpeningHours {
+        self.holidays.extend(dates);
+        self
+    }
+
+    /// The periods which start on `date`
+    #[must_use]
+    pub fn periods_on(&self, date: NaiveDate) -> Vec<(NaiveTime, NaiveTime)> {
+        if let Some(periods) = self.exceptions.get(&date) {
+            periods.clone()
+        } else if self.holidays.contains(&date) {
+            vec![]
+        } else {
+            let weekday = Weekday::from(date.weekday());
+            self.weekly
+                .iter()
+                .filter(|period| period.weekday == weekday)
+                .map(|period| (period.start, period.end))
+                .collect()
+        }
+    }
+
+    /// Whether it is open at `at`, as seen in the local time of its timezone
+    pub fn is_open_at<Tz: TimeZone>(&self, at: &DateTime<Tz>) -> bool {
+        let at = at.naive_local();
+        let date = at.date();
+        // Periods from the day before might run past midnight
+        date.pred_opt()
+            .into_iter()
+            .chain([date])
+            .flat_map(|date| self.intervals_on(date))
+            .any(|(start, end)| start <= at && at < end)
+    }
+
+    /// The next time it opens or closes after `at`,
+    /// or `None` if it never does again
+    pub fn next_transition<Tz: TimeZone>(
+        &self,
+        at: &DateTime<Tz>,
+    ) -> Option<Transition<Tz>> {
+        let now = at.naive_local();
+        let first = now.date().pred_opt()?;
+        // Past the last irregular date, a full week tells everything there is
+        let last_irregular = self
+            .exceptions
+            .keys()
+            .next_back()
+            .into_iter()
+            .chain(self.holidays.iter().next_back())
+            .fold(now.date(), |last, date| last.max(*date));
+        let horizon = last_irregular.checked_add_days(Days::new(8))?;
+
+        let mut intervals = first
+            .iter_days()
+            .take_while(|date| *date <= horizon)
+            .flat_map(|date| self.intervals_on(date))
+            .collect::<Vec<_>>();
+        intervals.sort();
+
+        // Overlapping or adjacent periods do not close in between
+        let mut merged: Vec<(NaiveDateTime, NaiveDateTime)> = vec![];
+        for (start, end) in intervals {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+
+        let horizon_start = horizon.and_time(NaiveTime::MIN);
+        for (start, end) in merged {
+            if start > now {
+                return Some(Transition {
+                    at: localize(&at.timezone(), start),
+                    opens: true,
+                });
+            }
+            if end > now {
+                // Open throughout the whole week past the horizon
+                if end >= horizon_start {
+                    return None;
+                }
+                return Some(Transition {
+                    at: localize(&at.timezone(), end),
+                    opens: false,
+                });
+            }
+        }
+        None
+    }
+
+    fn intervals_on(
+        &self,
+        date: NaiveDate,
+    ) -> impl Iterator<Item = (NaiveDateTime, NaiveDateTime)> {
+        self.periods_on(date).into_iter().map(move |(start, end)| {
+            let end = if end > start {
+                date.and_time(end)
+            } else {
+                date.and_time(end) + Days::new(1)
+            };
+            (date.and_time(start), end)
+        })
+    }
+}
+
+fn localize<Tz: TimeZone>(tz: &Tz, time: NaiveDateTime) -> DateTime<Tz> {
+    tz.from_local_datetime(&time)
+        .earliest()
+        // Skipped over by a daylight saving change
+        .or_else(|| {
+            tz.from_local_datetime(&(time + TimeDelta::hours(1)))
+                .earliest()
+        })
+        .unwrap_or_else(|| tz.from_utc_datetime(&time))
+}
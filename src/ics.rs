@@ -0,0 +1,367 @@
+//! iCalendar (RFC 5545) exports of student and teacher timetables.
+
+use crate::academic::AcademicCalendar;
+use crate::errors::Error;
+use crate::keys::{ClassInstanceKey, DepartmentKey};
+use crate::models::{
+    ClassInstance, ClassShift, ClassShiftInstance, Period, Student, Teacher,
+};
+use crate::{RequestConfig, CAMPUS_TIMEZONE};
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+/// Longest a line can be, in octets, before having to be folded
+const MAX_LINE_LENGTH: usize = 75;
+const DATETIME_FORMAT: &str = "%Y%m%dT%H%M%S";
+const UTC_DATETIME_FORMAT: &str = "%Y%m%dT%H%M%SZ";
+/// Definition of `CAMPUS_TIMEZONE`, as per the EU daylight saving rules
+const VTIMEZONE: &[&str] = &[
+    "BEGIN:VTIMEZONE",
+    "TZID:Europe/Lisbon",
+    "BEGIN:DAYLIGHT",
+    "TZOFFSETFROM:+0000",
+    "TZOFFSETTO:+0100",
+    "TZNAME:WEST",
+    "DTSTART:19700329T010000",
+    "RRULE:FREQ=YEARLY;BYMONTH=3;BYDAY=-1SU",
+    "END:DAYLIGHT",
+    "BEGIN:STANDARD",
+    "TZOFFSETFROM:+0100",
+    "TZOFFSETTO:+0000",
+    "TZNAME:WET",
+    "DTSTART:19701025T020000",
+    "RRULE:FREQ=YEARLY;BYMONTH=10;BYDAY=-1SU",
+    "END:STANDARD",
+    "END:VTIMEZONE",
+];
+
+/// Exports timetables in the iCalendar format (RFC 5545),
+/// such that calendar applications can import or subscribe to them.
+///
+/// Every lesson of a shift becomes an event repeating weekly throughout the
+/// term of its class instance, as set in the academic calendar, save for
+/// holidays.
+/// Shifts of class instances in a term the calendar doesn't know of are left
+/// out.
+/// Times are those of the campus, in its time zone (`CAMPUS_TIMEZONE`).
+#[derive(Debug, Clone)]
+pub struct IcsExporter {
+    name: Option<String>,
+    calendar: AcademicCalendar,
+}
+
+impl IcsExporter {
+    #[must_use]
+    pub fn new(calendar: AcademicCalendar) -> IcsExporter {
+        IcsExporter {
+            name: None,
+            calendar,
+        }
+    }
+
+    /// Names the calendar, as shown by calendar applications
+    #[must_use]
+    pub fn name(mut self, name: &str) -> IcsExporter {
+        self.name = Some(name.to_string());
+        self
+    }
+
+    /// The timetable of the shifts a student is enrolled in
+    pub async fn student(&self, student: &Student) -> Result<String, Error> {
+        // Shifts don't necessarily know their class instance, but the
+        // instances the student is enrolled in know their shifts
+        let mut class_instances = HashMap::new();
+        for enrollment in student.get_enrollments().await? {
+            let class_inst = enrollment.get_class_instance().await?;
+            class_instances.insert(class_inst.id, class_inst);
+        }
+        let shifts = student.get_shifts().await?;
+        self.export(&shifts, class_instances).await
+    }
+
+    /// The timetable of the shifts a teacher teaches.
+    ///
+    /// Shifts whose class instance isn't known yet are looked for within the
+    /// instances of the classes of the teacher's departments, of the terms
+    /// the calendar knows of.
+    /// Each class has its instances fetched most recent first, until one
+    /// older than every term, a few classes at a time (as many as set with
+    /// `SupernovaBuilder::concurrent_fetches`), until every shift is found.
+    pub async fn teacher(
+        &self,
+        teacher: &Teacher,
+        conf: &RequestConfig,
+    ) -> Result<String, Error> {
+        let shifts = teacher.get_shifts().await?;
+        let mut class_instances = HashMap::new();
+        let mut missing = HashSet::new();
+        for shift in &shifts {
+            match shift.get_class_instance().await? {
+                Some(class_inst) => {
+                    class_instances.insert(class_inst.id, class_inst);
+                }
+                None => {
+                    missing.insert(shift.id);
+                }
+            }
+        }
+
+        let terms: HashSet<(u32, Period)> = self
+            .calendar
+            .terms
+            .iter()
+            .map(|term| (term.year, term.period))
+            .collect();
+        let first_year = terms.iter().map(|(year, _)| *year).min();
+        if let (Some(first_year), false) = (first_year, missing.is_empty()) {
+            let client = &teacher.client;
+            let departments: HashSet<DepartmentKey> = teacher
+                .departments
+                .iter()
+                .map(|department_ref| department_ref.identifier)
+                .collect();
+            let classes = client.get_classes(conf).await?;
+            let sought = &missing.clone();
+            let known: &HashSet<ClassInstanceKey> =
+                &class_instances.keys().copied().collect();
+            let terms = &terms;
+            let mut scans = stream::iter(classes.iter().filter(|class| {
+                class.department.as_ref().is_some_and(|department_ref| {
+                    departments.contains(&department_ref.identifier)
+                })
+            }))
+            .map(|class| async move {
+                let mut candidates: Vec<ClassInstanceKey> = class
+                    .instances
+                    .iter()
+                    .map(|class_inst_ref| class_inst_ref.identifier)
+                    .filter(|id| !known.contains(id))
+                    .collect();
+                candidates.sort_by_key(|id| Reverse(*id));
+                let mut found = vec![];
+                let mut remaining = sought.clone();
+                for id in candidates {
+                    if remaining.is_empty() {
+                        break;
+                    }
+                    let class_inst =
+                        client.get_class_instance(id, conf).await?;
+                    if class_inst.year < first_year {
+                        break;
+                    }
+                    if !terms.contains(&(class_inst.year, class_inst.period)) {
+                        continue;
+                    }
+                    let before = remaining.len();
+                    for shift_ref in &class_inst.shifts {
+                        remaining.remove(&shift_ref.identifier);
+                    }
+                    if remaining.len() < before {
+                        found.push(class_inst);
+                    }
+                }
+                Ok::<_, Error>(found)
+            })
+            .buffer_unordered(client.concurrent_fetches);
+            while let Some(found) = scans.try_next().await? {
+                for class_inst in found {
+                    for shift_ref in &class_inst.shifts {
+                        missing.remove(&shift_ref.identifier);
+                    }
+                    class_instances.insert(class_inst.id, class_inst);
+                }
+                if missing.is_empty() {
+                    break;
+                }
+            }
+        }
+        self.export(&shifts, class_instances).await
+    }
+
+    /// The timetable of an arbitrary set of shifts, given the class instances
+    /// they belong to.
+    /// Shifts of none of these, nor of a class instance the client already
+    /// knows them to be part of, are left out.
+    pub async fn shifts(
+        &self,
+        shifts: &[ClassShift],
+        class_instances: &[ClassInstance],
+    ) -> Result<String, Error> {
+        let class_instances = class_instances
+            .iter()
+            .map(|class_inst| (class_inst.id, class_inst.clone()))
+            .collect();
+        self.export(shifts, class_instances).await
+    }
+
+    async fn export(
+        &self,
+        shifts: &[ClassShift],
+        mut class_instances: HashMap<ClassInstanceKey, ClassInstance>,
+    ) -> Result<String, Error> {
+        let mut calendar = String::new();
+        push_line(&mut calendar, "BEGIN:VCALENDAR");
+        push_line(&mut calendar, "VERSION:2.0");
+        push_line(&mut calendar, "PRODID:-//NovaUNL//IronPlanet//EN");
+        push_line(&mut calendar, "CALSCALE:GREGORIAN");
+        if let Some(name) = &self.name {
+            push_text(&mut calendar, "X-WR-CALNAME", name);
+        }
+        for line in VTIMEZONE {
+            push_line(&mut calendar, line);
+        }
+
+        let stamp = Utc::now().format(UTC_DATETIME_FORMAT).to_string();
+        let tzid = CAMPUS_TIMEZONE.name();
+        for shift in shifts {
+            if let Some(class_inst_ref) = &shift.class_instance {
+                if !class_instances.contains_key(&class_inst_ref.identifier) {
+                    let class_inst = class_inst_ref.coerce().await?;
+                    class_instances.insert(class_inst.id, class_inst);
+                }
+            }
+            let Some(class_inst) =
+                class_instances.values().find(|class_inst| {
+                    class_inst
+                        .shifts
+                        .iter()
+                        .any(|shift_ref| shift_ref.identifier == shift.id)
+                })
+            else {
+                continue;
+            };
+            let Some(term) =
+                self.calendar.term(class_inst.year, class_inst.period)
+            else {
+                continue;
+            };
+            let (first_day, last_day) = term.lessons;
+            let class = class_inst.get_class().await?;
+
+            let mut summary = format!(
+                "{} {}{}",
+                class.abbreviation,
+                shift.shift_type.abbreviation(),
+                shift.number
+            );
+            if shift.shift_type.is_online() {
+                summary.push_str(" (online)");
+            }
+
+            for (index, instance) in shift.instances.iter().enumerate() {
+                let occurrences = instance.occurrences(first_day, last_day);
+                let (Some((start, end)), Some((last_start, _))) =
+                    (occurrences.first(), occurrences.last())
+                else {
+                    continue;
+                };
+                let location = location(instance).await?;
+
+                push_line(&mut calendar, "BEGIN:VEVENT");
+                push_line(
+                    &mut calendar,
+                    &format!("UID:shift-{}-{index}@iron-planet", shift.id),
+                );
+                push_line(&mut calendar, &format!("DTSTAMP:{stamp}"));
+                push_line(
+                    &mut calendar,
+                    &format!("DTSTART;TZID={tzid}:{}", ics_datetime(*start)),
+                );
+                push_line(
+                    &mut calendar,
+                    &format!("DTEND;TZID={tzid}:{}", ics_datetime(*end)),
+                );
+                push_line(
+                    &mut calendar,
+                    &format!(
+                        "RRULE:FREQ=WEEKLY;UNTIL={}",
+                        ics_utc_datetime(*last_start)
+                    ),
+                );
+                let exceptions: Vec<String> = occurrences
+                    .iter()
+                    .filter(|(start, _)| self.calendar.is_holiday(start.date()))
+                    .map(|(start, _)| ics_datetime(*start))
+                    .collect();
+                if !exceptions.is_empty() {
+                    push_line(
+                        &mut calendar,
+                        &format!("EXDATE;TZID={tzid}:{}", exceptions.join(",")),
+                    );
+                }
+                push_text(&mut calendar, "SUMMARY", &summary);
+                push_text(&mut calendar, "DESCRIPTION", &class.name);
+                if let Some(location) = &location {
+                    push_text(&mut calendar, "LOCATION", location);
+                }
+                push_line(&mut calendar, "END:VEVENT");
+            }
+        }
+
+        push_line(&mut calendar, "END:VCALENDAR");
+        Ok(calendar)
+    }
+}
+
+/// The room of a lesson, along with its building
+async fn location(
+    instance: &ClassShiftInstance,
+) -> Result<Option<String>, Error> {
+    let Some(place) = instance.get_place().await? else {
+        return Ok(None);
+    };
+    Ok(Some(match place.get_building().await? {
+        Some(building) => format!("{}, {}", place.name, building.name),
+        None => place.name,
+    }))
+}
+
+fn ics_datetime(datetime: NaiveDateTime) -> String {
+    datetime.format(DATETIME_FORMAT).to_string()
+}
+
+/// A campus local `datetime` in UTC, as required of recurrence limits
+fn ics_utc_datetime(datetime: NaiveDateTime) -> String {
+    let utc = CAMPUS_TIMEZONE
+        .from_local_datetime(&datetime)
+        .earliest()
+        .map_or(datetime, |local| local.naive_utc());
+    utc.format(UTC_DATETIME_FORMAT).to_string()
+}
+
+/// Appends a property with a text value, escaping it as needed
+fn push_text(calendar: &mut String, name: &str, value: &str) {
+    let mut line = format!("{name}:");
+    for c in value.chars() {
+        match c {
+            '\\' | ';' | ',' => {
+                line.push('\\');
+                line.push(c);
+            }
+            '\n' => line.push_str("\\n"),
+            '\r' => {}
+            _ => line.push(c),
+        }
+    }
+    push_line(calendar, &line);
+}
+
+/// Appends a content line, folding it so that no line exceeds
+/// [`MAX_LINE_LENGTH`] octets
+fn push_line(calendar: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > MAX_LINE_LENGTH {
+            calendar.push_str("\r\n ");
+            // The leading space counts towards the length
+            length = 1;
+        }
+        calendar.push(c);
+        length += c.len_utf8();
+    }
+    calendar.push_str("\r\n");
+}
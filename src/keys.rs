@@ -9,6 +9,7 @@ pub type TeacherKey = u32;
 pub type StudentKey = u32;
 pub type ClassKey = u32;
 pub type ClassInstanceKey = u32;
+pub type ClassEventKey = u32;
 pub type ShiftKey = u32;
 pub type EnrollmentKey = u32;
 pub type FileKey = u32;
@@ -28,3 +29,6 @@ pub type LearningSubareaKey = u32;
 pub type LearningSectionKey = u32;
 pub type QuestionKey = u32;
 pub type ServiceKey = u32;
+
+pub type TransportationLineKey = u32;
+pub type TransportationStopKey = u32;
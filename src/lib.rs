@@ -11,37 +11,79 @@
 #![allow(clippy::missing_panics_doc)]
 #![allow(clippy::wildcard_imports)]
 
+use crate::download::PartialFile;
 use crate::errors::Error;
 use crate::keys::*;
 use crate::network::cache::ClientCache;
-use crate::network::endpoints::{
-    AuthenticatedSupernova, BaseSupernova, UPSTREAM,
-};
+use crate::network::endpoints::{AuthenticatedSupernova, BaseSupernova};
+use crate::network::http::Connection;
 use crate::network::models::{self as nmodels, AuthToken};
 use crate::nmodels::ClientMeta;
 use crate::utils::get_client_meta;
 
+use std::path::Path;
 use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+pub use crate::builder::SupernovaBuilder;
+pub use crate::download::DownloadProgress;
+pub use crate::network::endpoints::Endpoint;
+pub use crate::network::retry::RetryPolicy;
+pub use crate::network::transport::{
+    BodySink, HttpRequest, HttpResponse, HyperTransport, Method, PoolConfig,
+    Timeouts, Transport,
+};
+pub use crate::session::{FileTokenStore, TokenStore};
+pub use crate::sync::{
+    FileMirror, RemovalPolicy, SyncReport, ARCHIVE_DIR, MANIFEST_NAME,
+};
 
+pub mod academic;
+mod builder;
+pub mod calendar;
 pub(crate) mod coersion;
+mod download;
 pub mod errors;
+pub mod hours;
+pub mod ics;
 pub mod keys;
 pub mod models;
 mod network;
+pub mod rooms;
+mod session;
+mod sync;
+pub mod timetable;
 mod utils;
 
 const DEFAULT_PAGE_ITEM_LIMIT: u16 = 100;
+/// Timezone of the campus, in which dates and times are laid out
+pub const CAMPUS_TIMEZONE: chrono_tz::Tz = chrono_tz::Europe::Lisbon;
+/// How long the weather is cached for
+pub const WEATHER_TTL: Duration = Duration::from_secs(10 * 60);
 
-#[derive(Default)]
 pub struct Supernova {
     base: BaseSupernova,
     authenticated: AuthenticatedSupernova,
     cache: RwLock<ClientCache>,
+    connection: Arc<Connection>,
+    request_config: RequestConfig,
+    send_client_meta: bool,
+    concurrent_fetches: usize,
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct RequestConfig {
     pub evade_cache: bool,
+    /// Overrides of the client's timeouts (those left unset are kept)
+    pub timeouts: Timeouts,
+}
+
+impl Default for Supernova {
+    fn default() -> Self {
+        SupernovaBuilder::new().build_unwrapped()
+    }
 }
 
 impl Supernova {
@@ -50,6 +92,32 @@ impl Supernova {
         Arc::new(Supernova::default())
     }
 
+    /// Instantiates a client whose connection pool is tuned by `config`.
+    /// Every request issued by the client (and by the models linked to it)
+    /// reuses the connections of this pool.
+    #[must_use]
+    pub fn with_pool_config(config: &PoolConfig) -> Arc<Supernova> {
+        SupernovaBuilder::new().pool(config.clone()).build()
+    }
+
+    /// Instantiates a client that communicates through `transport`
+    /// instead of the default `HyperTransport`.
+    #[must_use]
+    pub fn with_transport(transport: Arc<dyn Transport>) -> Arc<Supernova> {
+        SupernovaBuilder::new().transport(transport).build()
+    }
+
+    #[must_use]
+    pub fn builder() -> SupernovaBuilder {
+        SupernovaBuilder::new()
+    }
+
+    /// Base URL of the upstream server this client talks to
+    #[must_use]
+    pub fn upstream(&self) -> &str {
+        &self.connection.upstream
+    }
+
     pub async fn login(
         &self,
         username: &str,
@@ -58,25 +126,59 @@ impl Supernova {
         let creds = nmodels::BasicAuthCredentials::new(
             username,
             password,
-            Some(get_client_meta()),
+            if self.send_client_meta {
+                Some(get_client_meta())
+            } else {
+                None
+            },
         );
-        let token = self.base.login(&creds).await?;
+        let token = self.base.login(&creds, &self.request_config).await?;
         self.authenticated.set_token(token.token.clone());
+        if let Some(store) = &self.authenticated.store {
+            store.save(self.upstream(), &token.token)?;
+        }
         Ok(token.token)
     }
 
     pub async fn logout(&self) -> Result<(), Error> {
-        self.authenticated.logout().await?;
+        self.authenticated.logout(&self.request_config).await?;
         self.authenticated.clear_token();
+        if let Some(store) = &self.authenticated.store {
+            store.remove(self.upstream())?;
+        }
         Ok(())
     }
 
     pub async fn set_auth_token(&self, token: AuthToken) -> Result<(), Error> {
-        self.base.verify(token.clone()).await?;
-        self.authenticated.set_token(token);
+        self.base
+            .verify(token.clone(), &self.request_config)
+            .await?;
+        self.authenticated.set_token(token.clone());
+        if let Some(store) = &self.authenticated.store {
+            store.save(self.upstream(), &token)?;
+        }
         Ok(())
     }
 
+    /// Resumes the session whose token is in the token store, if any.
+    /// Returns whether there was one.
+    ///
+    /// The token isn't verified, as that takes a round trip to the server.
+    /// Use `SupernovaBuilder::clear_invalid_tokens` to have the client
+    /// forget it, should it be rejected once used.
+    pub fn restore_session(&self) -> Result<bool, Error> {
+        let token = match &self.authenticated.store {
+            Some(store) => store.load(self.upstream())?,
+            None => None,
+        };
+        if let Some(token) = token {
+            self.authenticated.set_token(token);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
     pub fn is_authenticated(&self) -> bool {
         self.authenticated
             .credentials
@@ -102,7 +204,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_departments = self.base.fetch_departments().await?;
+        let net_departments = self.base.fetch_departments(conf).await?;
         {
             let mut cache = self.cache.write().unwrap();
             cache.departments_populated = true;
@@ -137,7 +239,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_buildings = self.base.fetch_buildings().await?;
+        let net_buildings = self.base.fetch_buildings(conf).await?;
         {
             let mut cache = self.cache.write().unwrap();
             cache.buildings_populated = true;
@@ -172,7 +274,7 @@ impl Supernova {
             }
         }
         // Drop read lock
-        let net_places = self.base.fetch_places().await?;
+        let net_places = self.base.fetch_places(conf).await?;
         {
             let mut cache = self.cache.write().unwrap();
             cache.places_populated = true;
@@ -207,7 +309,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_classes = self.base.fetch_classes().await?;
+        let net_classes = self.base.fetch_classes(conf).await?;
         {
             let mut cache = self.cache.write().unwrap();
             cache.classes_populated = true;
@@ -242,7 +344,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_courses = self.base.fetch_courses().await?;
+        let net_courses = self.base.fetch_courses(conf).await?;
         {
             let mut cache = self.cache.write().unwrap();
             cache.courses_populated = true;
@@ -274,7 +376,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_building = self.base.fetch_building(id).await?;
+        let net_building = self.base.fetch_building(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let building = net_building.link(&self.clone());
@@ -295,7 +397,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_place = self.base.fetch_place(id).await?;
+        let net_place = self.base.fetch_place(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let place = net_place.link(self.clone());
@@ -316,7 +418,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_department = self.base.fetch_department(id).await?;
+        let net_department = self.base.fetch_department(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let department = net_department.link(self.clone());
@@ -337,7 +439,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_course = self.base.fetch_course(id).await?;
+        let net_course = self.base.fetch_course(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let course = net_course.link(self.clone());
@@ -358,7 +460,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_class = self.base.fetch_class(id).await?;
+        let net_class = self.base.fetch_class(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let klass = net_class.link(&self.clone());
@@ -380,7 +482,7 @@ impl Supernova {
         } // Drop read lock
 
         let net_class_inst =
-            self.authenticated.fetch_class_instance(id).await?;
+            self.authenticated.fetch_class_instance(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         net_class_inst
@@ -392,13 +494,109 @@ impl Supernova {
                     .insert(net_enrollment.id, net_enrollment.clone());
             });
         net_class_inst.shifts.iter().for_each(|net_shift| {
+            cache
+                .shift_class_instances
+                .insert(net_shift.id, net_class_inst.id);
             cache.class_shifts.insert(net_shift.id, net_shift.clone());
         });
         let class_inst = net_class_inst.link(self.clone());
-
+        cache
+            .class_instances
+            .insert(net_class_inst.id, net_class_inst);
         Ok(class_inst)
     }
 
+    pub async fn get_class_instance_files(
+        self: &Arc<Supernova>,
+        id: keys::ClassInstanceKey,
+        conf: &RequestConfig,
+    ) -> Result<models::ClassInstanceFiles, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_files) = cache.class_instance_files.get(&id) {
+                return Ok(net_files.link(self));
+            }
+        } // Drop read lock
+
+        let net_files = self
+            .authenticated
+            .fetch_class_instance_files(id, conf)
+            .await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let files = net_files.link(self);
+        cache.class_instance_files.insert(id, net_files);
+        Ok(files)
+    }
+
+    /// Events of a class instance, sorted chronologically
+    pub async fn get_class_instance_events(
+        self: &Arc<Supernova>,
+        id: keys::ClassInstanceKey,
+        conf: &RequestConfig,
+    ) -> Result<Vec<models::ClassEvent>, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_events) = cache.class_instance_events.get(&id) {
+                return Ok(net_events
+                    .iter()
+                    .map(|net_event| net_event.link(self))
+                    .collect());
+            }
+        } // Drop read lock
+
+        let mut net_events = self
+            .authenticated
+            .fetch_class_instance_events(id, conf)
+            .await?;
+        net_events.sort_by_key(|net_event| (net_event.date, net_event.time));
+
+        let mut cache = self.cache.write().unwrap();
+        let events = net_events
+            .iter()
+            .map(|net_event| net_event.link(self))
+            .collect();
+        cache.class_instance_events.insert(id, net_events);
+        Ok(events)
+    }
+
+    /// Downloads `file` into `destination`, verifying it against its hash.
+    /// `on_progress` is told about every received chunk.
+    ///
+    /// The download is kept aside until verified, such that `destination`
+    /// is never left with a partial or corrupt file.
+    pub async fn download_file<P, F>(
+        &self,
+        file: &models::File,
+        destination: P,
+        mut on_progress: F,
+        conf: &RequestConfig,
+    ) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+        F: FnMut(DownloadProgress) + Send,
+    {
+        let mut partial = PartialFile::create(destination.as_ref())?;
+        let total = u64::from(file.size);
+        let result = self
+            .authenticated
+            .download(&file.url, conf, &mut |chunk| {
+                let received = partial.write(chunk)?;
+                on_progress(DownloadProgress { received, total });
+                Ok(())
+            })
+            .await;
+        match result {
+            Ok(()) => partial.finish(&file.hash),
+            Err(err) => {
+                partial.discard();
+                Err(err)
+            }
+        }
+    }
+
     pub async fn get_student(
         self: &Arc<Supernova>,
         id: keys::StudentKey,
@@ -412,7 +610,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_student = self.authenticated.fetch_student(id).await?;
+        let net_student = self.authenticated.fetch_student(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let student = net_student.link(self.clone());
@@ -433,7 +631,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_teacher = self.authenticated.fetch_teacher(id).await?;
+        let net_teacher = self.authenticated.fetch_teacher(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let teacher = net_teacher.link(self.clone());
@@ -441,6 +639,58 @@ impl Supernova {
         Ok(teacher)
     }
 
+    /// A user profile, linked to its students, teacher and groups
+    pub async fn get_user(
+        self: &Arc<Supernova>,
+        id: keys::UserKey,
+        conf: &RequestConfig,
+    ) -> Result<models::User, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_user) = cache.users.get(&id) {
+                return Ok(net_user.link(self));
+            }
+        } // Drop read lock
+
+        let net_user = self.base.fetch_user(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let user = net_user.link(self);
+        cache.users.insert(net_user.id, net_user);
+        Ok(user)
+    }
+
+    /// The profile of the authenticated user.
+    /// Kept for as long as the session lasts.
+    pub async fn me(
+        self: &Arc<Supernova>,
+        conf: &RequestConfig,
+    ) -> Result<models::CurrentUser, Error> {
+        let token = self.authenticated.token()?;
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some((owner, net_current_user)) = &cache.current_user {
+                // Sessions change on login, logout and token invalidation
+                if *owner == token {
+                    return Ok(net_current_user.link(self));
+                }
+            }
+        } // Drop read lock
+
+        let net_current_user =
+            self.authenticated.fetch_current_user(conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let current_user = net_current_user.link(self);
+        cache
+            .users
+            .insert(net_current_user.user.id, net_current_user.user.clone());
+        cache.current_user = Some((token, net_current_user));
+        Ok(current_user)
+    }
+
     pub async fn get_enrollment(
         self: &Arc<Supernova>,
         id: keys::ClassKey,
@@ -454,7 +704,8 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_enrollment = self.authenticated.fetch_enrollment(id).await?;
+        let net_enrollment =
+            self.authenticated.fetch_enrollment(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
         let enrollment = net_enrollment.link(self.clone());
@@ -471,14 +722,16 @@ impl Supernova {
             // Acquire read lock
             let cache = self.cache.read().unwrap();
             if let Some(net_shift) = cache.class_shifts.get(&id) {
-                return Ok(net_shift.link(&self.clone()));
+                let class_inst = cache.shift_class_instances.get(&id).copied();
+                return Ok(net_shift.link(&self.clone(), class_inst));
             }
         } // Drop read lock
 
-        let net_shift = self.authenticated.fetch_shift(id).await?;
+        let net_shift = self.authenticated.fetch_shift(id, conf).await?;
 
         let mut cache = self.cache.write().unwrap();
-        let shift = net_shift.link(&self.clone());
+        let class_inst = cache.shift_class_instances.get(&id).copied();
+        let shift = net_shift.link(&self.clone(), class_inst);
         cache.class_shifts.insert(net_shift.id, net_shift);
         Ok(shift)
     }
@@ -499,7 +752,7 @@ impl Supernova {
             }
         } // Drop read lock
 
-        let net_groups = self.base.fetch_groups().await?;
+        let net_groups = self.base.fetch_groups(conf).await?;
         {
             let mut cache = self.cache.write().unwrap();
             cache.groups_populated = true;
@@ -522,7 +775,17 @@ impl Supernova {
         self: &Arc<Supernova>,
         id: keys::GroupKey,
     ) -> Result<models::Group, Error> {
-        let net_group = self.base.fetch_group(id).await?;
+        self.get_group_with(id, &self.request_config).await
+    }
+
+    /// Same as [`Supernova::get_group`], with a request configuration
+    /// of its own
+    pub async fn get_group_with(
+        self: &Arc<Supernova>,
+        id: keys::GroupKey,
+        conf: &RequestConfig,
+    ) -> Result<models::Group, Error> {
+        let net_group = self.base.fetch_group(id, conf).await?;
         Ok(net_group.link(self))
     }
 
@@ -537,22 +800,46 @@ impl Supernova {
     pub async fn get_events_page(
         self: &Arc<Supernova>,
         key: EventsPageKey,
-        _conf: &RequestConfig,
+        conf: &RequestConfig,
     ) -> Result<Option<Arc<models::EventsPage>>, Error> {
-        let net_events_page = self.base.fetch_events(key).await?;
+        let net_events_page = self.base.fetch_events(key, conf).await?;
         if net_events_page.results.is_empty() {
             Ok(None)
         } else {
+            let mut cache = self.cache.write().unwrap();
+            for net_event in &net_events_page.results {
+                cache.detailed_events.remove(&net_event.id);
+                cache.events.insert(net_event.id, net_event.clone());
+            }
             Ok(Some(net_events_page.link(&self.clone(), key)))
         }
     }
 
+    /// An event, along with its sessions and enrollment details.
+    /// Events seen in pages lack these, and so are fetched again.
     pub async fn get_event(
         self: &Arc<Supernova>,
-        _key: EventKey,
-        _conf: &RequestConfig,
+        id: EventKey,
+        conf: &RequestConfig,
     ) -> Result<models::Event, Error> {
-        todo!()
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_event) = cache.events.get(&id) {
+                // Entries from pages lack the schedule and enrollment
+                if cache.detailed_events.contains(&id) {
+                    return Ok(net_event.link(self));
+                }
+            }
+        } // Drop read lock
+
+        let net_event = self.base.fetch_event(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let event = net_event.link(self);
+        cache.detailed_events.insert(net_event.id);
+        cache.events.insert(net_event.id, net_event);
+        Ok(event)
     }
 
     pub async fn get_news_front_page(
@@ -566,9 +853,9 @@ impl Supernova {
     pub async fn get_news_page(
         self: &Arc<Supernova>,
         key: NewsPageKey,
-        _conf: &RequestConfig,
+        conf: &RequestConfig,
     ) -> Result<Option<Arc<models::NewsPage>>, Error> {
-        let net_news_page = self.base.fetch_news(key).await?;
+        let net_news_page = self.base.fetch_news(key, conf).await?;
         if net_news_page.results.is_empty() {
             Ok(None)
         } else {
@@ -576,26 +863,263 @@ impl Supernova {
         }
     }
 
+    pub async fn get_learning_areas(
+        self: &Arc<Supernova>,
+        conf: &RequestConfig,
+    ) -> Result<Vec<models::LearningArea>, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if cache.learning_areas_populated {
+                return Ok(cache
+                    .learning_areas
+                    .values()
+                    .map(|net_area| net_area.link(self))
+                    .collect());
+            }
+        } // Drop read lock
+
+        let net_areas = self.base.fetch_learning_areas(conf).await?;
+        {
+            let mut cache = self.cache.write().unwrap();
+            cache.learning_areas_populated = true;
+            for net_area in net_areas {
+                cache.learning_areas.insert(net_area.id, net_area);
+            }
+        }
+        // Change write lock to read lock
+        {
+            let cache = self.cache.read().unwrap();
+            Ok(cache
+                .learning_areas
+                .values()
+                .map(|net_area| net_area.link(self))
+                .collect())
+        }
+    }
+
+    pub async fn get_learning_area(
+        self: &Arc<Supernova>,
+        id: keys::LearningAreaKey,
+        conf: &RequestConfig,
+    ) -> Result<models::LearningArea, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_area) = cache.learning_areas.get(&id) {
+                return Ok(net_area.link(self));
+            }
+        } // Drop read lock
+
+        let net_area = self.base.fetch_learning_area(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let learning_area = net_area.link(self);
+        cache.learning_areas.insert(net_area.id, net_area);
+        Ok(learning_area)
+    }
+
+    pub async fn get_learning_subarea(
+        self: &Arc<Supernova>,
+        id: keys::LearningSubareaKey,
+        conf: &RequestConfig,
+    ) -> Result<models::LearningSubarea, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_subarea) = cache.learning_subareas.get(&id) {
+                return Ok(net_subarea.link(self));
+            }
+        } // Drop read lock
+
+        let net_subarea = self.base.fetch_learning_subarea(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let learning_subarea = net_subarea.link(self);
+        cache.learning_subareas.insert(net_subarea.id, net_subarea);
+        Ok(learning_subarea)
+    }
+
+    pub async fn get_learning_section(
+        self: &Arc<Supernova>,
+        id: keys::LearningSectionKey,
+        conf: &RequestConfig,
+    ) -> Result<models::LearningSection, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_section) = cache.learning_sections.get(&id) {
+                return Ok(net_section.link(self));
+            }
+        } // Drop read lock
+
+        let net_section = self.base.fetch_learning_section(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let learning_section = net_section.link(self);
+        cache.learning_sections.insert(net_section.id, net_section);
+        Ok(learning_section)
+    }
+
+    pub async fn get_question(
+        self: &Arc<Supernova>,
+        id: keys::QuestionKey,
+        conf: &RequestConfig,
+    ) -> Result<models::Question, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_question) = cache.questions.get(&id) {
+                return Ok(net_question.link(self));
+            }
+        } // Drop read lock
+
+        let net_question = self.base.fetch_question(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let question = net_question.link(self);
+        cache.questions.insert(net_question.id, net_question);
+        Ok(question)
+    }
+
+    /// Today's public transportation timetable.
+    /// It is cached until the day is over on campus.
+    pub async fn get_transportation_day(
+        self: &Arc<Supernova>,
+        conf: &RequestConfig,
+    ) -> Result<models::TransportationDay, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_day) = &cache.transportation_day {
+                if net_day.date
+                    == Utc::now().with_timezone(&CAMPUS_TIMEZONE).date_naive()
+                {
+                    return Ok(net_day.to_model());
+                }
+            }
+        } // Drop read lock
+
+        let net_day = self.base.fetch_transportation_day(conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let day = net_day.to_model();
+        cache.transportation_day = Some(net_day);
+        Ok(day)
+    }
+
+    /// Current weather and forecasts.
+    /// These are cached for a few minutes (see `WEATHER_TTL`).
+    pub async fn get_weather(
+        self: &Arc<Supernova>,
+        conf: &RequestConfig,
+    ) -> Result<models::Weather, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some((fetched, net_weather)) = &cache.weather {
+                if fetched.elapsed() < WEATHER_TTL {
+                    return Ok(net_weather.to_model());
+                }
+            }
+        } // Drop read lock
+
+        let net_weather = self.base.fetch_weather(conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let weather = net_weather.to_model();
+        cache.weather = Some((Instant::now(), net_weather));
+        Ok(weather)
+    }
+
+    /// Campus services (canteens, libraries, reprography, ...), along with
+    /// their opening hours. See [`Supernova::get_service`] for their menus.
+    pub async fn get_services(
+        self: &Arc<Supernova>,
+        conf: &RequestConfig,
+    ) -> Result<Vec<models::Service>, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if cache.services_populated {
+                return Ok(cache
+                    .services
+                    .values()
+                    .map(|net_service| net_service.link(self))
+                    .collect());
+            }
+        } // Drop read lock
+
+        let net_services = self.base.fetch_services(conf).await?;
+        {
+            let mut cache = self.cache.write().unwrap();
+            cache.services_populated = true;
+            // Listings lack the menus, and leave out services gone upstream
+            cache.detailed_services.clear();
+            cache.services = net_services
+                .into_iter()
+                .map(|net_service| (net_service.id, net_service))
+                .collect();
+        }
+        // Change write lock to read lock
+        {
+            let cache = self.cache.read().unwrap();
+            Ok(cache
+                .services
+                .values()
+                .map(|net_service| net_service.link(self))
+                .collect())
+        }
+    }
+
+    /// A service, along with its menu, if it has one
+    pub async fn get_service(
+        self: &Arc<Supernova>,
+        id: keys::ServiceKey,
+        conf: &RequestConfig,
+    ) -> Result<models::Service, Error> {
+        if !conf.evade_cache {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            if let Some(net_service) = cache.services.get(&id) {
+                // Entries from listings lack the menu
+                if cache.detailed_services.contains(&id) {
+                    return Ok(net_service.link(self));
+                }
+            }
+        } // Drop read lock
+
+        let net_service = self.base.fetch_service(id, conf).await?;
+
+        let mut cache = self.cache.write().unwrap();
+        let service = net_service.link(self);
+        cache.detailed_services.insert(net_service.id);
+        cache.services.insert(net_service.id, net_service);
+        Ok(service)
+    }
+
     pub async fn load_resource(
         self: &Arc<Supernova>,
         url: &str,
     ) -> Result<Vec<u8>, Error> {
-        let url = format!("{}{}", *UPSTREAM, url);
+        let url = format!("{}{}", self.upstream(), url);
         if self.is_authenticated() {
-            self.authenticated.fetch_bytes(&url).await
+            self.authenticated
+                .fetch_bytes(&url, &self.request_config)
+                .await
         } else {
-            self.base.fetch_bytes(&url).await
+            self.base.fetch_bytes(&url, &self.request_config).await
         }
     }
 
     pub async fn warmup(self: &Arc<Supernova>) -> Result<(), Error> {
-        let conf = RequestConfig::default();
-        self.get_buildings(&conf).await?;
-        self.get_courses(&conf).await?;
-        self.get_classes(&conf).await?;
-        self.get_departments(&conf).await?;
-        self.get_places(&conf).await?;
-        self.get_groups(&conf).await?;
+        let conf = &self.request_config;
+        self.get_buildings(conf).await?;
+        self.get_courses(conf).await?;
+        self.get_classes(conf).await?;
+        self.get_departments(conf).await?;
+        self.get_places(conf).await?;
+        self.get_groups(conf).await?;
         Ok(())
     }
 }
@@ -1,8 +1,10 @@
 use crate::coersion::ObjRef;
 use crate::errors::Error;
+use crate::hours::{OpeningHours, OpeningPeriod};
 use crate::keys::*;
 pub use crate::network::models::{ClassInfo, ClassInfoEntry, ClassInfoSources};
-use crate::Supernova;
+use crate::rooms::{RoomOccupancy, RoomSchedule};
+use crate::{RequestConfig, Supernova};
 
 use std::cell::Cell;
 use std::cmp::Ordering;
@@ -10,20 +12,48 @@ use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::sync::Arc;
 
-use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, Utc};
 use once_cell::sync::OnceCell;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd)]
 pub enum Weekday {
     Monday,
-    Thursday,
-    Wednesday,
     Tuesday,
+    Wednesday,
+    Thursday,
     Friday,
     Saturday,
     Sunday,
 }
 
+impl From<chrono::Weekday> for Weekday {
+    fn from(weekday: chrono::Weekday) -> Self {
+        match weekday {
+            chrono::Weekday::Mon => Weekday::Monday,
+            chrono::Weekday::Tue => Weekday::Tuesday,
+            chrono::Weekday::Wed => Weekday::Wednesday,
+            chrono::Weekday::Thu => Weekday::Thursday,
+            chrono::Weekday::Fri => Weekday::Friday,
+            chrono::Weekday::Sat => Weekday::Saturday,
+            chrono::Weekday::Sun => Weekday::Sunday,
+        }
+    }
+}
+
+impl From<Weekday> for chrono::Weekday {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Monday => chrono::Weekday::Mon,
+            Weekday::Tuesday => chrono::Weekday::Tue,
+            Weekday::Wednesday => chrono::Weekday::Wed,
+            Weekday::Thursday => chrono::Weekday::Thu,
+            Weekday::Friday => chrono::Weekday::Fri,
+            Weekday::Saturday => chrono::Weekday::Sat,
+            Weekday::Sunday => chrono::Weekday::Sun,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd)]
 pub enum Period {
     Year,
@@ -59,6 +89,32 @@ pub enum ShiftType {
     OnlinePracticalTheoretical,
 }
 
+impl ShiftType {
+    /// How the type is usually abbreviated (eg. `TP` for practical-theoretical)
+    #[must_use]
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            ShiftType::Theoretical | ShiftType::OnlineTheoretical => "T",
+            ShiftType::Practical | ShiftType::OnlinePractical => "P",
+            ShiftType::PracticalTheoretical
+            | ShiftType::OnlinePracticalTheoretical => "TP",
+            ShiftType::Seminar => "S",
+            ShiftType::TutorialOrientation => "OT",
+            ShiftType::FieldWork => "TC",
+        }
+    }
+
+    #[must_use]
+    pub fn is_online(self) -> bool {
+        matches!(
+            self,
+            ShiftType::OnlineTheoretical
+                | ShiftType::OnlinePractical
+                | ShiftType::OnlinePracticalTheoretical
+        )
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Ord, PartialOrd)]
 pub enum FileCategory {
     Image,
@@ -224,6 +280,9 @@ pub struct ClassInstance {
     pub avg_grade: Option<f32>,
     pub(crate) shifts: Vec<ObjRef<ClassShift, ShiftKey>>,
     pub(crate) department: Option<ObjRef<Department, DepartmentKey>>,
+    pub(crate) parent: ObjRef<Class, ClassKey>,
+    pub(crate) files: ObjRef<ClassInstanceFiles, ClassInstanceKey>,
+    pub(crate) events: ObjRef<Vec<ClassEvent>, ClassInstanceKey>,
 }
 
 // pub struct ClassInfo {
@@ -254,6 +313,7 @@ pub struct ClassShift {
     pub shift_type: ShiftType,
     pub(crate) teachers: Vec<ObjRef<Teacher, TeacherKey>>,
     pub instances: Vec<ClassShiftInstance>,
+    pub(crate) class_instance: Option<ObjRef<ClassInstance, ClassInstanceKey>>,
 }
 
 #[derive(Debug, Clone)]
@@ -264,6 +324,24 @@ pub struct ClassShiftInstance {
     pub(crate) room: Option<ObjRef<Place, PlaceKey>>,
 }
 
+/// An evaluation, delivery or other happening of a class instance
+#[derive(Debug, Clone)]
+pub struct ClassEvent {
+    pub id: ClassEventKey,
+    pub event_type: ClassEventType,
+    /// The evaluation season, for evaluations
+    pub season: Option<Season>,
+    pub date: NaiveDate,
+    /// Unset for events that last the whole day (eg. deadlines)
+    pub time: Option<NaiveTime>,
+    /// In minutes
+    pub duration: Option<u16>,
+    /// Who can see the event
+    pub visibility: FileVisibility,
+    pub description: Option<String>,
+    pub(crate) place: Option<ObjRef<Place, PlaceKey>>,
+}
+
 #[derive(Debug, Clone)]
 pub struct ClassInstanceFiles {
     pub official: Vec<ClassInstanceFile>,
@@ -395,7 +473,11 @@ impl Building {
             Some(if let Some(bytes) = self.thumb_cache.get() {
                 Ok(bytes.clone())
             } else {
-                let response = self.client.base.fetch_bytes(thumb_url).await;
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(thumb_url, &self.client.request_config)
+                    .await;
                 if let Ok(bytes) = &response {
                     let _ = self.thumb_cache.set(bytes.clone());
                 }
@@ -411,7 +493,11 @@ impl Building {
             Some(if let Some(bytes) = self.cover_cache.get() {
                 Ok(bytes.clone())
             } else {
-                let response = self.client.base.fetch_bytes(cover_url).await;
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(cover_url, &self.client.request_config)
+                    .await;
 
                 if let Ok(bytes) = &response {
                     let _ = self.cover_cache.set(bytes.clone());
@@ -464,12 +550,26 @@ impl Place {
         })
     }
 
+    /// The lessons taking place here throughout the week, as per
+    /// `occupancy` (see [`Supernova::room_occupancy`])
+    pub async fn schedule(
+        &self,
+        occupancy: &RoomOccupancy,
+        conf: &RequestConfig,
+    ) -> Result<RoomSchedule, Error> {
+        RoomSchedule::new(&self.client, occupancy, self.id, conf).await
+    }
+
     pub async fn cover_bytes(&self) -> Option<Result<Vec<u8>, Error>> {
         if let Some(cover_url) = &self.cover {
             Some(if let Some(bytes) = self.cover_cache.get() {
                 Ok(bytes.clone())
             } else {
-                let response = self.client.base.fetch_bytes(cover_url).await;
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(cover_url, &self.client.request_config)
+                    .await;
 
                 if let Ok(bytes) = &response {
                     let _ = self.cover_cache.set(bytes.clone());
@@ -600,6 +700,11 @@ impl Hash for Class {
 }
 
 impl ClassInstance {
+    /// The class this is an instance of
+    pub async fn get_class(&self) -> Result<Class, Error> {
+        self.parent.coerce().await
+    }
+
     pub async fn get_department(&self) -> Result<Option<Department>, Error> {
         Ok(if let Some(department) = &self.department {
             Some(department.coerce().await?)
@@ -623,6 +728,58 @@ impl ClassInstance {
         }
         Ok(result)
     }
+
+    /// The official and community-uploaded files of this instance.
+    /// See `Supernova::download_file` to download them.
+    pub async fn files(&self) -> Result<ClassInstanceFiles, Error> {
+        self.files.coerce().await
+    }
+
+    /// Tests, exams, deliveries and other events, sorted chronologically
+    pub async fn events(&self) -> Result<Vec<ClassEvent>, Error> {
+        self.events.coerce().await
+    }
+}
+
+impl ClassEvent {
+    pub async fn get_place(&self) -> Result<Option<Place>, Error> {
+        Ok(if let Some(place) = &self.place {
+            Some(place.coerce().await?)
+        } else {
+            None
+        })
+    }
+
+    /// When the event starts, if it has a set time
+    #[must_use]
+    pub fn start(&self) -> Option<NaiveDateTime> {
+        self.time.map(|time| self.date.and_time(time))
+    }
+
+    /// When the event ends, if it has a set time and duration
+    #[must_use]
+    pub fn end(&self) -> Option<NaiveDateTime> {
+        let duration = TimeDelta::minutes(i64::from(self.duration?));
+        Some(self.start()? + duration)
+    }
+}
+
+impl PartialEq for ClassEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for ClassEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.date, self.time).partial_cmp(&(other.date, other.time))
+    }
+}
+
+impl Hash for ClassEvent {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
 }
 
 impl PartialEq for ClassInstance {
@@ -709,7 +866,11 @@ impl Teacher {
             Some(if let Some(bytes) = self.thumb_cache.get() {
                 Ok(bytes.clone())
             } else {
-                let response = self.client.base.fetch_bytes(thumb_url).await;
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(thumb_url, &self.client.request_config)
+                    .await;
 
                 if let Ok(bytes) = &response {
                     let _ = self.thumb_cache.set(bytes.clone());
@@ -775,6 +936,17 @@ impl ClassShift {
         }
         Ok(result)
     }
+
+    /// The class instance this shift belongs to, if known
+    pub async fn get_class_instance(
+        &self,
+    ) -> Result<Option<ClassInstance>, Error> {
+        Ok(if let Some(class_inst_ref) = &self.class_instance {
+            Some(class_inst_ref.coerce().await?)
+        } else {
+            None
+        })
+    }
 }
 
 impl PartialEq for ClassShift {
@@ -861,9 +1033,122 @@ impl Hash for ClassInstanceFile {
 
 // ------------ Users ---------------
 
-#[derive(Debug, PartialEq, Copy, Clone)]
+#[derive(Clone)]
 pub struct User {
     pub id: UserKey,
+    pub nickname: String,
+    pub name: String,
+    pub picture: Option<String>,
+    pub social_links: Vec<SocialLink>,
+    pub url: String,
+    pub(crate) students: Vec<ObjRef<Student, StudentKey>>,
+    pub(crate) teacher: Option<ObjRef<Teacher, TeacherKey>>,
+    pub(crate) groups: Vec<ObjRef<Group, GroupKey>>,
+
+    pub(crate) client: Arc<Supernova>,
+    pub(crate) picture_cache: OnceCell<Vec<u8>>,
+}
+
+/// The user whom the session belongs to
+#[derive(Debug, Clone)]
+pub struct CurrentUser {
+    /// Links to the students and teacher of this user
+    pub profile: User,
+    pub permissions: Vec<String>,
+}
+
+impl CurrentUser {
+    #[must_use]
+    pub fn has_permission(&self, permission: &str) -> bool {
+        self.permissions.iter().any(|granted| granted == permission)
+    }
+}
+
+/// A profile of the user on some other website
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SocialLink {
+    pub network: String,
+    pub url: String,
+}
+
+impl User {
+    /// The student records of this user (one per enrolled course)
+    pub async fn get_students(&self) -> Result<Vec<Student>, Error> {
+        let mut result = vec![];
+        for student_ref in &self.students {
+            result.push(student_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+
+    pub async fn get_teacher(&self) -> Result<Option<Teacher>, Error> {
+        Ok(if let Some(teacher_ref) = &self.teacher {
+            Some(teacher_ref.coerce().await?)
+        } else {
+            None
+        })
+    }
+
+    pub async fn get_groups(&self) -> Result<Vec<Group>, Error> {
+        let mut result = vec![];
+        for group_ref in &self.groups {
+            result.push(group_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+
+    pub async fn picture_bytes(&self) -> Option<Result<Vec<u8>, Error>> {
+        if let Some(picture_url) = &self.picture {
+            Some(if let Some(bytes) = self.picture_cache.get() {
+                Ok(bytes.clone())
+            } else {
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(picture_url, &self.client.request_config)
+                    .await;
+
+                if let Ok(bytes) = &response {
+                    let _ = self.picture_cache.set(bytes.clone());
+                }
+
+                response
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl PartialEq for User {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for User {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.nickname.partial_cmp(&other.nickname)
+    }
+}
+
+impl Hash for User {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl fmt::Debug for User {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("User")
+            .field("id", &self.id)
+            .field("nickname", &self.nickname)
+            .field("name", &self.name)
+            .field("picture", &self.picture)
+            .field("social_links", &self.social_links)
+            .field("url", &self.url)
+            .finish()
+    }
 }
 
 // ------------ Groups --------------
@@ -984,6 +1269,30 @@ pub struct Event {
     pub capacity: Option<u32>,
     pub cost: Option<u32>,
     pub event_type: GroupEventType,
+    pub(crate) group: Option<ObjRef<Group, GroupKey>>,
+    /// Every session of the event, for those spanning several
+    pub schedule: Vec<EventSession>,
+    pub enrollment: Option<EventEnrollment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EventSession {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Where it takes place, if other than the event's place
+    pub(crate) place: Option<ObjRef<Place, PlaceKey>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EventEnrollment {
+    /// Whether attendees need to enroll beforehand
+    pub required: bool,
+    /// Whether enrollments are currently being accepted
+    pub open: bool,
+    pub deadline: Option<DateTime<Utc>>,
+    pub enrolled: u32,
+    /// Where to enroll, if elsewhere
+    pub url: Option<String>,
 }
 
 impl Event {
@@ -994,6 +1303,36 @@ impl Event {
             Ok(None)
         }
     }
+
+    /// The group organizing this event
+    pub async fn group(&self) -> Result<Option<Group>, Error> {
+        if let Some(group) = &self.group {
+            Ok(Some(group.coerce().await?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// How many more people can enroll, if the capacity is limited
+    #[must_use]
+    pub fn spots_left(&self) -> Option<u32> {
+        let enrolled = self
+            .enrollment
+            .as_ref()
+            .map_or(0, |enrollment| enrollment.enrolled);
+        self.capacity
+            .map(|capacity| capacity.saturating_sub(enrolled))
+    }
+}
+
+impl EventSession {
+    pub async fn place(&self) -> Result<Option<Place>, Error> {
+        if let Some(place) = &self.place {
+            Ok(Some(place.coerce().await?))
+        } else {
+            Ok(None)
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -1024,7 +1363,11 @@ impl Group {
             Some(if let Some(bytes) = self.thumb_cache.get() {
                 Ok(bytes.clone())
             } else {
-                let response = self.client.base.fetch_bytes(thumb_url).await;
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(thumb_url, &self.client.request_config)
+                    .await;
 
                 if let Ok(bytes) = &response {
                     let _ = self.thumb_cache.set(bytes.clone());
@@ -1239,7 +1582,11 @@ impl NewsItem {
             Some(if let Some(bytes) = self.thumb_cache.get() {
                 Ok(bytes.clone())
             } else {
-                let response = self.client.base.fetch_bytes(thumb_url).await;
+                let response = self
+                    .client
+                    .base
+                    .fetch_bytes(thumb_url, &self.client.request_config)
+                    .await;
 
                 if let Ok(bytes) = &response {
                     let _ = self.thumb_cache.set(bytes.clone());
@@ -1271,3 +1618,577 @@ impl fmt::Debug for NewsItem {
             .finish()
     }
 }
+
+// ------------ Learning --------------
+
+#[derive(Debug, Clone)]
+pub struct LearningArea {
+    pub id: LearningAreaKey,
+    pub title: String,
+    pub description: Option<String>,
+    pub(crate) subareas: Vec<ObjRef<LearningSubarea, LearningSubareaKey>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LearningSubarea {
+    pub id: LearningSubareaKey,
+    pub title: String,
+    pub description: Option<String>,
+    pub(crate) area: ObjRef<LearningArea, LearningAreaKey>,
+    pub(crate) sections: Vec<ObjRef<LearningSection, LearningSectionKey>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LearningSection {
+    pub id: LearningSectionKey,
+    pub title: String,
+    pub description: Option<String>,
+    pub content: Option<String>,
+    pub(crate) subarea: Option<ObjRef<LearningSubarea, LearningSubareaKey>>,
+    pub(crate) parents: Vec<ObjRef<LearningSection, LearningSectionKey>>,
+    pub(crate) children: Vec<ObjRef<LearningSection, LearningSectionKey>>,
+    pub(crate) questions: Vec<ObjRef<Question, QuestionKey>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Question {
+    pub id: QuestionKey,
+    pub title: String,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    pub answers: Vec<QuestionAnswer>,
+    pub(crate) author: Option<ObjRef<User, UserKey>>,
+    pub(crate) sections: Vec<ObjRef<LearningSection, LearningSectionKey>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuestionAnswer {
+    pub id: u32,
+    pub content: String,
+    pub timestamp: DateTime<Utc>,
+    /// Whether this answer was accepted as the solution to the question
+    pub accepted: bool,
+    pub(crate) author: Option<ObjRef<User, UserKey>>,
+}
+
+impl LearningArea {
+    pub async fn get_subareas(&self) -> Result<Vec<LearningSubarea>, Error> {
+        let mut result = vec![];
+        for subarea_ref in &self.subareas {
+            result.push(subarea_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+}
+
+impl PartialEq for LearningArea {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for LearningArea {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.title.partial_cmp(&other.title)
+    }
+}
+
+impl Hash for LearningArea {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl LearningSubarea {
+    pub async fn get_area(&self) -> Result<LearningArea, Error> {
+        self.area.coerce().await
+    }
+
+    pub async fn get_sections(&self) -> Result<Vec<LearningSection>, Error> {
+        let mut result = vec![];
+        for section_ref in &self.sections {
+            result.push(section_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+}
+
+impl PartialEq for LearningSubarea {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for LearningSubarea {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.title.partial_cmp(&other.title)
+    }
+}
+
+impl Hash for LearningSubarea {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl LearningSection {
+    pub async fn get_subarea(&self) -> Result<Option<LearningSubarea>, Error> {
+        Ok(if let Some(subarea) = &self.subarea {
+            Some(subarea.coerce().await?)
+        } else {
+            None
+        })
+    }
+
+    /// Sections that this one builds upon
+    pub async fn get_parents(&self) -> Result<Vec<LearningSection>, Error> {
+        let mut result = vec![];
+        for section_ref in &self.parents {
+            result.push(section_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+
+    /// Sections that build upon this one
+    pub async fn get_children(&self) -> Result<Vec<LearningSection>, Error> {
+        let mut result = vec![];
+        for section_ref in &self.children {
+            result.push(section_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+
+    pub async fn get_questions(&self) -> Result<Vec<Question>, Error> {
+        let mut result = vec![];
+        for question_ref in &self.questions {
+            result.push(question_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+}
+
+impl PartialEq for LearningSection {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for LearningSection {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.title.partial_cmp(&other.title)
+    }
+}
+
+impl Hash for LearningSection {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Question {
+    pub async fn author(&self) -> Result<Option<User>, Error> {
+        Ok(if let Some(author) = &self.author {
+            Some(author.coerce().await?)
+        } else {
+            None
+        })
+    }
+
+    pub async fn get_sections(&self) -> Result<Vec<LearningSection>, Error> {
+        let mut result = vec![];
+        for section_ref in &self.sections {
+            result.push(section_ref.coerce().await?);
+        }
+        Ok(result)
+    }
+
+    #[must_use]
+    pub fn accepted_answer(&self) -> Option<&QuestionAnswer> {
+        self.answers.iter().find(|answer| answer.accepted)
+    }
+
+    /// Whether every word of `query` is in the title or in the content,
+    /// regardless of the case.
+    #[must_use]
+    pub fn matches(&self, query: &str) -> bool {
+        let title = self.title.to_lowercase();
+        let content = self.content.to_lowercase();
+        query
+            .to_lowercase()
+            .split_whitespace()
+            .all(|word| title.contains(word) || content.contains(word))
+    }
+}
+
+impl PartialEq for Question {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for Question {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.timestamp.partial_cmp(&other.timestamp)
+    }
+}
+
+impl Hash for Question {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl QuestionAnswer {
+    pub async fn author(&self) -> Result<Option<User>, Error> {
+        Ok(if let Some(author) = &self.author {
+            Some(author.coerce().await?)
+        } else {
+            None
+        })
+    }
+}
+
+impl PartialEq for QuestionAnswer {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+// ------------ Transportation --------------
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum TransportationMode {
+    Bus,
+    Ferry,
+    Train,
+    Metro,
+    Other,
+}
+
+/// The public transportation that serves the campus throughout a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportationDay {
+    pub date: NaiveDate,
+    pub lines: Vec<TransportationLine>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportationLine {
+    pub id: TransportationLineKey,
+    pub name: String,
+    pub mode: TransportationMode,
+    pub stops: Vec<TransportationStop>,
+    pub trips: Vec<TransportationTrip>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransportationStop {
+    pub id: TransportationStopKey,
+    pub name: String,
+}
+
+/// A single run of a line, through its stops
+#[derive(Debug, Clone, PartialEq)]
+pub struct TransportationTrip {
+    pub destination: Option<String>,
+    /// Stops in the order they are served, along with the time they are
+    pub stops: Vec<(TransportationStopKey, NaiveTime)>,
+}
+
+/// A trip leaving a stop
+#[derive(Debug, Clone, PartialEq)]
+pub struct Departure<'a> {
+    pub line: &'a TransportationLine,
+    pub trip: &'a TransportationTrip,
+    pub time: NaiveTime,
+}
+
+impl TransportationDay {
+    /// Every stop served by any of the lines
+    #[must_use]
+    pub fn stops(&self) -> Vec<&TransportationStop> {
+        let mut stops: Vec<&TransportationStop> = vec![];
+        for stop in self.lines.iter().flat_map(|line| &line.stops) {
+            if !stops.iter().any(|known| known.id == stop.id) {
+                stops.push(stop);
+            }
+        }
+        stops
+    }
+
+    /// Stops whose name contains `name`, regardless of the case
+    #[must_use]
+    pub fn find_stops(&self, name: &str) -> Vec<&TransportationStop> {
+        let name = name.to_lowercase();
+        self.stops()
+            .into_iter()
+            .filter(|stop| stop.name.to_lowercase().contains(&name))
+            .collect()
+    }
+
+    /// Departures from `stop` at or after `after`, soonest first.
+    /// Trips that end at the stop don't depart from it.
+    #[must_use]
+    pub fn next_departures(
+        &self,
+        stop: TransportationStopKey,
+        after: NaiveTime,
+    ) -> Vec<Departure<'_>> {
+        let mut departures: Vec<Departure> = self
+            .lines
+            .iter()
+            .flat_map(|line| line.departures(stop).into_iter())
+            .filter(|departure| departure.time >= after)
+            .collect();
+        departures.sort_by_key(|departure| departure.time);
+        departures
+    }
+}
+
+impl TransportationLine {
+    /// Every departure of this line from `stop`, in no particular order
+    #[must_use]
+    pub fn departures(
+        &self,
+        stop: TransportationStopKey,
+    ) -> Vec<Departure<'_>> {
+        self.trips
+            .iter()
+            .filter_map(|trip| {
+                trip.departure_time(stop).map(|time| Departure {
+                    line: self,
+                    trip,
+                    time,
+                })
+            })
+            .collect()
+    }
+}
+
+impl TransportationTrip {
+    /// When the trip leaves `stop`, if it does
+    #[must_use]
+    pub fn departure_time(
+        &self,
+        stop: TransportationStopKey,
+    ) -> Option<NaiveTime> {
+        let (_, departing) = self.stops.split_last()?;
+        departing
+            .iter()
+            .find(|(key, _)| *key == stop)
+            .map(|(_, time)| *time)
+    }
+
+    /// When the trip reaches its last stop
+    #[must_use]
+    pub fn arrival_time(&self) -> Option<NaiveTime> {
+        self.stops.last().map(|(_, time)| *time)
+    }
+}
+
+// ------------ Weather --------------
+
+/// The sky, as described by the IPMA weather type codes
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum WeatherCondition {
+    Clear,
+    PartlyCloudy,
+    Cloudy,
+    Showers,
+    Drizzle,
+    Rain,
+    HeavyRain,
+    Mist,
+    Fog,
+    Snow,
+    Hail,
+    Frost,
+    Thunderstorm,
+    Unknown(u8),
+}
+
+impl WeatherCondition {
+    #[must_use]
+    pub fn from_code(code: u8) -> WeatherCondition {
+        match code {
+            1 => WeatherCondition::Clear,
+            2 | 3 | 25 => WeatherCondition::PartlyCloudy,
+            4 | 5 | 24 | 27 => WeatherCondition::Cloudy,
+            6 | 7 | 12 | 13 => WeatherCondition::Showers,
+            15 => WeatherCondition::Drizzle,
+            9 | 10 => WeatherCondition::Rain,
+            8 | 11 | 14 => WeatherCondition::HeavyRain,
+            16 => WeatherCondition::Mist,
+            17 | 26 => WeatherCondition::Fog,
+            18 => WeatherCondition::Snow,
+            21 => WeatherCondition::Hail,
+            22 => WeatherCondition::Frost,
+            19 | 20 | 23 => WeatherCondition::Thunderstorm,
+            _ => WeatherCondition::Unknown(code),
+        }
+    }
+
+    /// Whether something is falling from the sky
+    #[must_use]
+    pub fn is_wet(&self) -> bool {
+        matches!(
+            self,
+            WeatherCondition::Showers
+                | WeatherCondition::Drizzle
+                | WeatherCondition::Rain
+                | WeatherCondition::HeavyRain
+                | WeatherCondition::Snow
+                | WeatherCondition::Hail
+                | WeatherCondition::Thunderstorm
+        )
+    }
+}
+
+/// Current conditions and forecasts for the campus.
+/// Temperatures are in degrees Celsius, precipitation in millimeters,
+/// wind speeds in kilometers per hour and wind directions in degrees
+/// (clockwise from north).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weather {
+    pub current: CurrentWeather,
+    pub hourly: Vec<HourlyForecast>,
+    pub daily: Vec<DailyForecast>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentWeather {
+    pub time: DateTime<Utc>,
+    pub temperature: f32,
+    /// Relative humidity, in percentage
+    pub humidity: Option<u8>,
+    pub precipitation: f32,
+    pub wind_speed: f32,
+    pub wind_direction: Option<u16>,
+    pub condition: WeatherCondition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HourlyForecast {
+    pub time: DateTime<Utc>,
+    pub temperature: f32,
+    pub precipitation: f32,
+    /// In percentage
+    pub precipitation_probability: Option<u8>,
+    pub wind_speed: f32,
+    pub wind_direction: Option<u16>,
+    pub condition: WeatherCondition,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyForecast {
+    pub date: NaiveDate,
+    pub min_temperature: f32,
+    pub max_temperature: f32,
+    pub precipitation: f32,
+    /// In percentage
+    pub precipitation_probability: Option<u8>,
+    pub wind_speed: f32,
+    pub condition: WeatherCondition,
+}
+
+impl Weather {
+    /// The hourly forecast for the hour that contains `time`
+    #[must_use]
+    pub fn forecast_at(&self, time: DateTime<Utc>) -> Option<&HourlyForecast> {
+        self.hourly.iter().find(|forecast| {
+            forecast.time <= time
+                && time < forecast.time + chrono::Duration::hours(1)
+        })
+    }
+
+    /// The forecast for `date`
+    #[must_use]
+    pub fn forecast_on(&self, date: NaiveDate) -> Option<&DailyForecast> {
+        self.daily.iter().find(|forecast| forecast.date == date)
+    }
+}
+
+// ------------ Services --------------
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ServiceType {
+    Canteen,
+    Cafeteria,
+    Bar,
+    Library,
+    Reprography,
+    Store,
+    Other,
+}
+
+/// A campus service, such as a canteen or a library
+#[derive(Debug, Clone)]
+pub struct Service {
+    pub id: ServiceKey,
+    pub name: String,
+    pub service_type: ServiceType,
+    pub description: Option<String>,
+    pub opening_hours: OpeningHours,
+    /// Menus of the upcoming days, if this service has any.
+    /// Only known once the service is fetched by itself.
+    pub menu: Option<Vec<MenuDay>>,
+    pub(crate) place: Option<ObjRef<Place, PlaceKey>>,
+}
+
+/// A weekly period throughout which a service is open
+#[deprecated(
+    note = "Use `hours::OpeningPeriod`, as in `Service::opening_hours`"
+)]
+pub type ServiceOpeningHours = OpeningPeriod;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuDay {
+    pub date: NaiveDate,
+    pub items: Vec<MenuItem>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MenuItem {
+    pub name: String,
+    /// Eg. soup, meat, fish or dessert
+    pub category: Option<String>,
+    /// In euros
+    pub price: Option<f32>,
+}
+
+impl Service {
+    pub async fn get_place(&self) -> Result<Option<Place>, Error> {
+        Ok(if let Some(place) = &self.place {
+            Some(place.coerce().await?)
+        } else {
+            None
+        })
+    }
+
+    /// The menu of `date`, if known
+    #[must_use]
+    pub fn menu_on(&self, date: NaiveDate) -> Option<&MenuDay> {
+        self.menu
+            .as_ref()?
+            .iter()
+            .find(|menu_day| menu_day.date == date)
+    }
+}
+
+impl PartialEq for Service {
+    fn eq(&self, other: &Self) -> bool {
+        self.id.eq(&other.id)
+    }
+}
+
+impl PartialOrd for Service {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.name.partial_cmp(&other.name)
+    }
+}
+
+impl Hash for Service {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
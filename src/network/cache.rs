@@ -1,6 +1,7 @@
 use crate::keys;
 use crate::network::models as nmodels;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Default)]
@@ -12,15 +13,43 @@ pub(crate) struct ClientCache {
     pub(crate) classes: HashMap<keys::ClassKey, nmodels::Class>,
     pub(crate) classes_populated: bool,
     pub(crate) class_instances: HashMap<keys::ClassInstanceKey, nmodels::ClassInstance>,
+    pub(crate) class_instance_files:
+        HashMap<keys::ClassInstanceKey, nmodels::ClassInstanceFiles>,
+    pub(crate) class_instance_events:
+        HashMap<keys::ClassInstanceKey, Vec<nmodels::ClassEvent>>,
     pub(crate) class_shifts: HashMap<keys::ShiftKey, nmodels::ClassShift>,
+    /// Class instances shifts were seen within
+    pub(crate) shift_class_instances: HashMap<keys::ShiftKey, keys::ClassInstanceKey>,
     pub(crate) buildings: HashMap<keys::BuildingKey, nmodels::Building>,
     pub(crate) buildings_populated: bool,
     pub(crate) places: HashMap<keys::PlaceKey, nmodels::Place>,
     pub(crate) places_populated: bool,
     pub(crate) students: HashMap<keys::StudentKey, nmodels::Student>,
     pub(crate) teachers: HashMap<keys::TeacherKey, nmodels::Teacher>,
+    pub(crate) users: HashMap<keys::UserKey, nmodels::User>,
+    /// The current user, along with the token it was obtained with
+    pub(crate) current_user: Option<(nmodels::AuthToken, nmodels::CurrentUser)>,
+    pub(crate) events: HashMap<keys::EventKey, nmodels::Event>,
+    /// Events fetched by themselves, which unlike those from pages
+    /// come with their schedule and enrollment
+    pub(crate) detailed_events: HashSet<keys::EventKey>,
     pub(crate) enrollments: HashMap<keys::EnrollmentKey, nmodels::Enrollment>,
 
     pub(crate) groups: HashMap<keys::GroupKey, nmodels::WeakGroup>,
     pub(crate) groups_populated: bool,
+
+    pub(crate) learning_areas: HashMap<keys::LearningAreaKey, nmodels::LearningArea>,
+    pub(crate) learning_areas_populated: bool,
+    pub(crate) learning_subareas: HashMap<keys::LearningSubareaKey, nmodels::LearningSubarea>,
+    pub(crate) learning_sections: HashMap<keys::LearningSectionKey, nmodels::LearningSection>,
+    pub(crate) questions: HashMap<keys::QuestionKey, nmodels::Question>,
+
+    pub(crate) transportation_day: Option<nmodels::TransportationDay>,
+    pub(crate) weather: Option<(Instant, nmodels::Weather)>,
+
+    pub(crate) services: HashMap<keys::ServiceKey, nmodels::Service>,
+    pub(crate) services_populated: bool,
+    /// Services fetched by themselves, which unlike those from listings
+    /// come with their menu, if they have one
+    pub(crate) detailed_services: HashSet<keys::ServiceKey>,
 }
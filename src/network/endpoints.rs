@@ -1,41 +1,28 @@
 use crate::keys::*;
+use crate::network::transport::{BodySink, Method};
 use crate::network::{http::*, models as nmodels};
-use crate::AuthToken;
+use crate::session::TokenStore;
 use crate::{keys, Error};
+use crate::{AuthToken, RequestConfig};
 
 use std::borrow::BorrowMut;
 use std::cell::RefCell;
-use std::env;
 use std::fmt;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
-use hyper::Method;
-use lazy_static::lazy_static;
 use serde::de::DeserializeOwned;
 
-lazy_static! {
-    pub(crate) static ref UPSTREAM: String = {
-        let default = "https://supernova.nunl.pt";
-        // Allowing this override in release builds would allow token hijacking
-        if cfg!(debug_assertions){
-            if let Ok(url) = env::var("SUPERNOVA_UPSTREAM") {
-                url
-            } else {
-                default.to_string()
-            }
-        } else {
-            default.to_string()
-        }
-    };
-}
-
+/// A resource of the Supernova API
 #[allow(dead_code)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Endpoint {
     Login,
     Logout,
     TokenValidation,
 
     Profile(UserKey),
+    /// The profile of the authenticated user
+    CurrentUser,
 
     Buildings,
     Building(BuildingKey),
@@ -48,6 +35,8 @@ pub enum Endpoint {
     Classes,
     Class(ClassKey),
     ClassInstance(ClassInstanceKey),
+    ClassInstanceFiles(ClassInstanceKey),
+    ClassInstanceEvents(ClassInstanceKey),
     Student(StudentKey),
     Teacher(TeacherKey),
     Enrollment(EnrollmentKey),
@@ -56,6 +45,7 @@ pub enum Endpoint {
     Groups,
     Group(GroupKey),
     EventsPage(EventsPageKey),
+    Event(EventKey),
 
     NewsItemPage(NewsPageKey),
 
@@ -73,9 +63,9 @@ pub enum Endpoint {
     Service(ServiceKey),
 }
 
+/// Endpoints display as their path, relative to the upstream server
 impl fmt::Display for Endpoint {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let _ = f.write_str(&UPSTREAM);
         let _ = f.write_str("/api/");
         match self {
             Endpoint::Login => f.write_str("login"),
@@ -83,8 +73,9 @@ impl fmt::Display for Endpoint {
             Endpoint::TokenValidation => f.write_str("validation"),
 
             Endpoint::Profile(id) => {
-                f.write_fmt(format_args!("services/{}", id))
+                f.write_fmt(format_args!("profile/{}", id))
             }
+            Endpoint::CurrentUser => f.write_str("profile/me"),
 
             Endpoint::Buildings => f.write_str("buildings"),
             Endpoint::Building(id) => {
@@ -103,6 +94,12 @@ impl fmt::Display for Endpoint {
             Endpoint::ClassInstance(id) => {
                 f.write_fmt(format_args!("class/i/{}", id))
             }
+            Endpoint::ClassInstanceFiles(id) => {
+                f.write_fmt(format_args!("class/i/{id}/files"))
+            }
+            Endpoint::ClassInstanceEvents(id) => {
+                f.write_fmt(format_args!("class/i/{id}/events"))
+            }
             Endpoint::Student(id) => {
                 f.write_fmt(format_args!("student/{}", id))
             }
@@ -120,6 +117,7 @@ impl fmt::Display for Endpoint {
                 "events?limit={}&offset={}",
                 limit, offset
             )),
+            Endpoint::Event(id) => f.write_fmt(format_args!("event/{id}")),
 
             Endpoint::NewsItemPage((limit, offset)) => f.write_fmt(
                 format_args!("news?limit={}&offset={}", limit, offset),
@@ -149,167 +147,310 @@ impl fmt::Display for Endpoint {
     }
 }
 
-#[derive(Default)]
-pub(crate) struct BaseSupernova {}
+pub(crate) struct BaseSupernova {
+    connection: Arc<Connection>,
+}
 
 impl BaseSupernova {
-    #[allow(clippy::unused_self)]
+    pub(crate) fn new(connection: Arc<Connection>) -> BaseSupernova {
+        BaseSupernova { connection }
+    }
+
     async fn generic_fetch<T: DeserializeOwned>(
         &self,
-        url: &str,
+        endpoint: &Endpoint,
+        conf: &RequestConfig,
     ) -> Result<T, Error> {
-        let response = Request::new(&url).send().await?;
-        response.deserialize().await
+        let response = self
+            .connection
+            .endpoint(endpoint)
+            .timeouts(conf.timeouts)
+            .retrying()
+            .send()
+            .await?;
+        response.deserialize()
     }
 
-    #[allow(clippy::unused_self)]
     pub(crate) async fn fetch_bytes(
         &self,
         url: &str,
+        conf: &RequestConfig,
     ) -> Result<Vec<u8>, Error> {
-        let response = if url.starts_with('/') {
-            let url = format!("{}{}", *UPSTREAM, url);
-            Request::new(&url)
-        } else {
-            Request::new(url)
-        }
-        .send()
-        .await?;
-        response.to_vec().await
+        let response = self
+            .connection
+            .request(url)
+            .timeouts(conf.timeouts)
+            .retrying()
+            .send()
+            .await?;
+        Ok(response.to_vec())
     }
 
-    #[allow(clippy::unused_self)]
     pub(crate) async fn login<'creds>(
         &self,
         credentials: &nmodels::BasicAuthCredentials<'creds>,
+        conf: &RequestConfig,
     ) -> Result<nmodels::TokenResult, Error> {
-        let response = Request::new(&Endpoint::Login.to_string())
+        let response = self
+            .connection
+            .endpoint(&Endpoint::Login)
+            .timeouts(conf.timeouts)
             .send_serializable(credentials)
             .await?;
 
-        response.deserialize().await
+        response.deserialize()
     }
 
-    #[allow(clippy::unused_self)]
-    pub(crate) async fn verify(&self, token: AuthToken) -> Result<(), Error> {
-        let response = Request::new(&Endpoint::TokenValidation.to_string())
+    pub(crate) async fn verify(
+        &self,
+        token: AuthToken,
+        conf: &RequestConfig,
+    ) -> Result<(), Error> {
+        let response = self
+            .connection
+            .endpoint(&Endpoint::TokenValidation)
             .attach_token(&token)
+            .timeouts(conf.timeouts)
             .send()
             .await?;
 
-        let response_text: String = response.deserialize().await?;
+        let response_text: String = response.deserialize()?;
         if response_text == "Success" {
             Ok(())
         } else {
-            Err(Error::Authentication)
+            Err(Error::Authentication(None))
         }
     }
 
     pub(crate) async fn fetch_departments(
         &self,
+        conf: &RequestConfig,
     ) -> Result<Vec<nmodels::Department>, Error> {
-        self.generic_fetch(&Endpoint::Departments.to_string()).await
+        self.generic_fetch(&Endpoint::Departments, conf).await
     }
 
     pub(crate) async fn fetch_buildings(
         &self,
+        conf: &RequestConfig,
     ) -> Result<Vec<nmodels::Building>, Error> {
-        self.generic_fetch(&Endpoint::Buildings.to_string()).await
+        self.generic_fetch(&Endpoint::Buildings, conf).await
     }
 
     pub(crate) async fn fetch_classes(
         &self,
+        conf: &RequestConfig,
     ) -> Result<Vec<nmodels::Class>, Error> {
-        self.generic_fetch(&Endpoint::Classes.to_string()).await
+        self.generic_fetch(&Endpoint::Classes, conf).await
     }
 
     pub(crate) async fn fetch_courses(
         &self,
+        conf: &RequestConfig,
     ) -> Result<Vec<nmodels::Course>, Error> {
-        self.generic_fetch(&Endpoint::Courses.to_string()).await
+        self.generic_fetch(&Endpoint::Courses, conf).await
     }
 
     pub(crate) async fn fetch_places(
         &self,
+        conf: &RequestConfig,
     ) -> Result<Vec<nmodels::Place>, Error> {
-        self.generic_fetch(&Endpoint::Places.to_string()).await
+        self.generic_fetch(&Endpoint::Places, conf).await
     }
 
     pub(crate) async fn fetch_building(
         &self,
         key: keys::BuildingKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Building, Error> {
-        self.generic_fetch(&Endpoint::Building(key).to_string())
-            .await
+        self.generic_fetch(&Endpoint::Building(key), conf).await
     }
 
     pub(crate) async fn fetch_place(
         &self,
         key: keys::RoomKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Place, Error> {
-        self.generic_fetch(&Endpoint::Place(key).to_string()).await
+        self.generic_fetch(&Endpoint::Place(key), conf).await
     }
 
     pub(crate) async fn fetch_department(
         &self,
         key: keys::DepartmentKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Department, Error> {
-        self.generic_fetch(&Endpoint::Department(key).to_string())
-            .await
+        self.generic_fetch(&Endpoint::Department(key), conf).await
     }
 
     pub(crate) async fn fetch_course(
         &self,
         key: keys::CourseKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Course, Error> {
-        self.generic_fetch(&Endpoint::Course(key).to_string()).await
+        self.generic_fetch(&Endpoint::Course(key), conf).await
     }
 
     pub(crate) async fn fetch_class(
         &self,
         key: keys::ClassKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Class, Error> {
-        self.generic_fetch(&Endpoint::Class(key).to_string()).await
+        self.generic_fetch(&Endpoint::Class(key), conf).await
     }
 
     pub(crate) async fn fetch_groups(
         &self,
+        conf: &RequestConfig,
     ) -> Result<Vec<nmodels::WeakGroup>, Error> {
         let endpoint = Endpoint::Groups;
-        self.generic_fetch(&endpoint.to_string()).await
+        self.generic_fetch(&endpoint, conf).await
     }
 
     pub(crate) async fn fetch_group(
         &self,
         key: GroupKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Group, Error> {
         let endpoint = Endpoint::Group(key);
-        self.generic_fetch(&endpoint.to_string()).await
+        self.generic_fetch(&endpoint, conf).await
     }
 
     pub(crate) async fn fetch_events(
         &self,
         key: keys::EventsPageKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::EventsPage, Error> {
         let endpoint = Endpoint::EventsPage(key);
-        self.generic_fetch(&endpoint.to_string()).await
+        self.generic_fetch(&endpoint, conf).await
+    }
+
+    pub(crate) async fn fetch_event(
+        &self,
+        key: keys::EventKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::Event, Error> {
+        self.generic_fetch(&Endpoint::Event(key), conf).await
     }
 
     pub(crate) async fn fetch_news(
         &self,
         key: keys::NewsPageKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::NewsPage, Error> {
         let endpoint = Endpoint::NewsItemPage(key);
-        self.generic_fetch(&endpoint.to_string()).await
+        self.generic_fetch(&endpoint, conf).await
+    }
+
+    pub(crate) async fn fetch_learning_areas(
+        &self,
+        conf: &RequestConfig,
+    ) -> Result<Vec<nmodels::LearningArea>, Error> {
+        self.generic_fetch(&Endpoint::LearningAreas, conf).await
+    }
+
+    pub(crate) async fn fetch_learning_area(
+        &self,
+        key: keys::LearningAreaKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::LearningArea, Error> {
+        self.generic_fetch(&Endpoint::LearningArea(key), conf).await
+    }
+
+    pub(crate) async fn fetch_learning_subarea(
+        &self,
+        key: keys::LearningSubareaKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::LearningSubarea, Error> {
+        self.generic_fetch(&Endpoint::LearningSubarea(key), conf)
+            .await
+    }
+
+    pub(crate) async fn fetch_learning_section(
+        &self,
+        key: keys::LearningSectionKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::LearningSection, Error> {
+        self.generic_fetch(&Endpoint::LearningSection(key), conf)
+            .await
+    }
+
+    pub(crate) async fn fetch_question(
+        &self,
+        key: keys::QuestionKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::Question, Error> {
+        self.generic_fetch(&Endpoint::Question(key), conf).await
+    }
+
+    pub(crate) async fn fetch_transportation_day(
+        &self,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::TransportationDay, Error> {
+        self.generic_fetch(&Endpoint::Transportation, conf).await
+    }
+
+    pub(crate) async fn fetch_weather(
+        &self,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::Weather, Error> {
+        self.generic_fetch(&Endpoint::Weather, conf).await
+    }
+
+    pub(crate) async fn fetch_user(
+        &self,
+        key: keys::UserKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::User, Error> {
+        self.generic_fetch(&Endpoint::Profile(key), conf).await
+    }
+
+    pub(crate) async fn fetch_services(
+        &self,
+        conf: &RequestConfig,
+    ) -> Result<Vec<nmodels::Service>, Error> {
+        self.generic_fetch(&Endpoint::Services, conf).await
+    }
+
+    pub(crate) async fn fetch_service(
+        &self,
+        key: keys::ServiceKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::Service, Error> {
+        self.generic_fetch(&Endpoint::Service(key), conf).await
     }
 }
 
-#[derive(Default)]
+pub(crate) type TokenCallback = Arc<dyn Fn(&AuthToken) + Send + Sync>;
+
+/// What happens when the server rejects the stored token
+#[derive(Clone, Default)]
+pub(crate) struct TokenInvalidation {
+    /// Whether to forget the token
+    pub(crate) clear: bool,
+    /// Notified with the rejected token
+    pub(crate) callback: Option<TokenCallback>,
+}
+
 pub(crate) struct AuthenticatedSupernova {
     pub(crate) credentials: Mutex<RefCell<Option<AuthToken>>>,
+    connection: Arc<Connection>,
+    invalidation: TokenInvalidation,
+    pub(crate) store: Option<Arc<dyn TokenStore>>,
 }
 
 impl AuthenticatedSupernova {
+    pub(crate) fn new(
+        connection: Arc<Connection>,
+        invalidation: TokenInvalidation,
+        store: Option<Arc<dyn TokenStore>>,
+    ) -> AuthenticatedSupernova {
+        AuthenticatedSupernova {
+            credentials: Mutex::default(),
+            connection,
+            invalidation,
+            store,
+        }
+    }
+
     pub(crate) fn set_token(&self, token: AuthToken) {
         self.credentials
             .lock()
@@ -326,107 +467,170 @@ impl AuthenticatedSupernova {
             .swap(&RefCell::new(None));
     }
 
-    async fn generic_fetch<T: DeserializeOwned>(
-        &self,
-        url: &str,
-    ) -> Result<T, Error> {
-        let token = self
-            .credentials
+    pub(crate) fn token(&self) -> Result<AuthToken, Error> {
+        self.credentials
             .lock()
             .unwrap()
             .borrow()
             .clone()
-            .ok_or(Error::MissingAuthentication)?;
-
-        let response = Request::new(&url).attach_token(&token).send().await?;
+            .ok_or(Error::MissingAuthentication)
+    }
 
-        response.deserialize().await
+    /// Sends `request` with the stored token,
+    /// reacting to the server rejecting it.
+    async fn send(&self, request: Request) -> Result<Response, Error> {
+        let token = self.token()?;
+        let result = request.attach_token(&token).send().await;
+        if let Err(Error::Authentication(_)) = result {
+            self.token_rejected(&token);
+        }
+        result
     }
 
-    #[allow(clippy::unused_self)]
-    pub(crate) async fn fetch_bytes(
+    /// Streams the resource at `url` into `sink`.
+    /// The token is sent along if there is one, as not every file is public.
+    pub(crate) async fn download(
         &self,
         url: &str,
-    ) -> Result<Vec<u8>, Error> {
-        if let Some(credentials) =
-            self.credentials.lock().unwrap().borrow().as_ref()
-        {
-            let request = if url.starts_with('/') {
-                let url = format!("{}{}", *UPSTREAM, url);
-                Request::new(&url)
-            } else {
-                Request::new(url)
-            };
-
-            let response = request.attach_token(&credentials).send().await?;
-
-            response.to_vec().await
+        conf: &RequestConfig,
+        sink: &mut BodySink<'_>,
+    ) -> Result<(), Error> {
+        let request = self.connection.request(url).timeouts(conf.timeouts);
+        if let Ok(token) = self.token() {
+            let result = request.attach_token(&token).stream(sink).await;
+            if let Err(Error::Authentication(_)) = result {
+                self.token_rejected(&token);
+            }
+            result.map(|_| ())
         } else {
-            Err(Error::MissingAuthentication)
+            request.stream(sink).await.map(|_| ())
         }
     }
 
-    pub(crate) async fn logout(&self) -> Result<(), Error> {
-        if let Some(credentials) =
-            self.credentials.lock().unwrap().borrow().as_ref()
-        {
-            let response = Request::new(&Endpoint::Logout.to_string())
-                .attach_token(credentials)
-                .method(Method::DELETE)
-                .send()
-                .await?;
-
-            let status_code = response.code();
-            if status_code.is_success() {
-                Ok(())
-            } else if status_code.is_server_error() {
-                Err(Error::Server)
-            } else if status_code.is_client_error() {
-                Err(Error::Client)
-            } else {
-                Err(Error::Generic)
+    fn token_rejected(&self, token: &AuthToken) {
+        if self.invalidation.clear {
+            let credentials = self.credentials.lock().unwrap();
+            if credentials.borrow().as_ref() != Some(token) {
+                // Already replaced or cleared, and notified
+                return;
             }
-        } else {
-            Err(Error::MissingAuthentication)
+            credentials.replace(None);
+            if let Some(store) = &self.store {
+                // Nothing to be done about it at this point
+                let _ = store.remove(&self.connection.upstream);
+            }
+        }
+        if let Some(callback) = &self.invalidation.callback {
+            callback(token);
         }
     }
 
+    async fn generic_fetch<T: DeserializeOwned>(
+        &self,
+        endpoint: &Endpoint,
+        conf: &RequestConfig,
+    ) -> Result<T, Error> {
+        let request = self
+            .connection
+            .endpoint(endpoint)
+            .timeouts(conf.timeouts)
+            .retrying();
+        let response = self.send(request).await?;
+
+        response.deserialize()
+    }
+
+    pub(crate) async fn fetch_bytes(
+        &self,
+        url: &str,
+        conf: &RequestConfig,
+    ) -> Result<Vec<u8>, Error> {
+        let request = self
+            .connection
+            .request(url)
+            .timeouts(conf.timeouts)
+            .retrying();
+        let response = self.send(request).await?;
+
+        Ok(response.to_vec())
+    }
+
+    pub(crate) async fn logout(
+        &self,
+        conf: &RequestConfig,
+    ) -> Result<(), Error> {
+        let request = self
+            .connection
+            .endpoint(&Endpoint::Logout)
+            .method(Method::Delete)
+            .timeouts(conf.timeouts);
+        self.send(request).await?;
+        Ok(())
+    }
+
     pub(crate) async fn fetch_class_instance(
         &self,
         key: keys::ClassInstanceKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::ClassInstance, Error> {
-        self.generic_fetch(&Endpoint::ClassInstance(key).to_string())
+        self.generic_fetch(&Endpoint::ClassInstance(key), conf)
+            .await
+    }
+
+    pub(crate) async fn fetch_class_instance_files(
+        &self,
+        key: keys::ClassInstanceKey,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::ClassInstanceFiles, Error> {
+        self.generic_fetch(&Endpoint::ClassInstanceFiles(key), conf)
+            .await
+    }
+
+    pub(crate) async fn fetch_class_instance_events(
+        &self,
+        key: keys::ClassInstanceKey,
+        conf: &RequestConfig,
+    ) -> Result<Vec<nmodels::ClassEvent>, Error> {
+        self.generic_fetch(&Endpoint::ClassInstanceEvents(key), conf)
             .await
     }
 
     pub(crate) async fn fetch_student(
         &self,
         key: keys::StudentKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Student, Error> {
-        self.generic_fetch(&Endpoint::Student(key).to_string())
-            .await
+        self.generic_fetch(&Endpoint::Student(key), conf).await
+    }
+
+    pub(crate) async fn fetch_current_user(
+        &self,
+        conf: &RequestConfig,
+    ) -> Result<nmodels::CurrentUser, Error> {
+        self.generic_fetch(&Endpoint::CurrentUser, conf).await
     }
 
     pub(crate) async fn fetch_teacher(
         &self,
         key: keys::TeacherKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Teacher, Error> {
-        self.generic_fetch(&Endpoint::Teacher(key).to_string())
-            .await
+        self.generic_fetch(&Endpoint::Teacher(key), conf).await
     }
 
     pub(crate) async fn fetch_enrollment(
         &self,
         key: keys::TeacherKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::Enrollment, Error> {
-        self.generic_fetch(&Endpoint::Enrollment(key).to_string())
-            .await
+        self.generic_fetch(&Endpoint::Enrollment(key), conf).await
     }
 
     pub(crate) async fn fetch_shift(
         &self,
         key: keys::TeacherKey,
+        conf: &RequestConfig,
     ) -> Result<nmodels::ClassShift, Error> {
-        self.generic_fetch(&Endpoint::Shift(key).to_string()).await
+        self.generic_fetch(&Endpoint::Shift(key), conf).await
     }
 }
@@ -1,33 +1,79 @@
-use crate::errors::Error;
+use crate::errors::{Error, HttpError};
+use crate::network::endpoints::Endpoint;
+use crate::network::models as nmodels;
+use crate::network::retry::RetryPolicy;
+use crate::network::transport::{
+    BodySink, HttpRequest, HttpResponse, Method, Timeouts, Transport,
+};
 
-use hyper::body::Buf;
-use hyper::client::HttpConnector;
-use hyper::http::request;
-use hyper::{Body, Client, Method, Response as HyperResponse, StatusCode};
-use hyper_tls::HttpsConnector;
+use std::sync::Arc;
+
+use hyper::StatusCode;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-pub(crate) struct Request {
-    builder: request::Builder,
-    client: Client<HttpsConnector<HttpConnector>>,
+/// The means to reach an upstream server, shared by every request to it
+pub(crate) struct Connection {
+    pub(crate) transport: Arc<dyn Transport>,
+    pub(crate) upstream: String,
+    pub(crate) user_agent: String,
+    pub(crate) timeouts: Timeouts,
+    pub(crate) retry_policy: Arc<RetryPolicy>,
 }
 
-impl Request {
-    pub(crate) fn new(url: &str) -> Request {
-        let builder = request::Builder::new()
-            .uri(url)
-            .method(Method::GET)
-            .header("iron_planet", env!("CARGO_PKG_VERSION"));
+impl Connection {
+    /// A request to `url`, which is relative to the upstream if it starts
+    /// with a slash, and absolute otherwise.
+    pub(crate) fn request(&self, url: &str) -> Request {
+        if url.starts_with('/') {
+            Request::new(self, &format!("{}{}", self.upstream, url))
+        } else {
+            Request::new(self, url)
+        }
+    }
 
-        let https = HttpsConnector::new();
-        let client = Client::builder().build::<_, hyper::Body>(https);
+    pub(crate) fn endpoint(&self, endpoint: &Endpoint) -> Request {
+        let mut request = self.request(&endpoint.to_string());
+        request.endpoint = Some(endpoint.clone());
+        request
+    }
+}
 
-        Request { builder, client }
+pub(crate) struct Request {
+    transport: Arc<dyn Transport>,
+    retry_policy: Arc<RetryPolicy>,
+    retrying: bool,
+    endpoint: Option<Endpoint>,
+    inner: HttpRequest,
+}
+
+impl Request {
+    fn new(connection: &Connection, url: &str) -> Request {
+        let inner = HttpRequest {
+            method: Method::Get,
+            url: url.to_string(),
+            headers: vec![
+                (
+                    "iron_planet".to_string(),
+                    env!("CARGO_PKG_VERSION").to_string(),
+                ),
+                ("User-Agent".to_string(), connection.user_agent.clone()),
+            ],
+            body: vec![],
+            timeouts: connection.timeouts,
+        };
+
+        Request {
+            transport: connection.transport.clone(),
+            retry_policy: connection.retry_policy.clone(),
+            retrying: false,
+            endpoint: None,
+            inner,
+        }
     }
 
     pub(crate) fn header(mut self, k: String, v: String) -> Request {
-        self.builder = self.builder.header(k, v);
+        self.inner.headers.push((k, v));
         self
     }
 
@@ -36,77 +82,149 @@ impl Request {
     }
 
     pub(crate) fn method(mut self, method: Method) -> Request {
-        self.builder = self.builder.method(method);
+        self.inner.method = method;
         self
     }
 
-    pub(crate) async fn body(self, body: Body) -> Result<Response, Error> {
-        let response = self
-            .client
-            .request(self.builder.body(body).map_err(|_| Error::Generic)?)
-            .await
-            .map_err(|_| Error::Network)?;
+    /// Overrides the default timeouts with those that are set in `timeouts`
+    pub(crate) fn timeouts(mut self, timeouts: Timeouts) -> Request {
+        self.inner.timeouts = timeouts.or(self.inner.timeouts);
+        self
+    }
 
-        check_error(response.status())?;
+    /// Retries the request on transient failures, as per the retry policy.
+    /// Only to be used with idempotent requests.
+    pub(crate) fn retrying(mut self) -> Request {
+        self.retrying = true;
+        self
+    }
 
-        Ok(Response(response))
+    pub(crate) async fn body(
+        mut self,
+        body: Vec<u8>,
+    ) -> Result<Response, Error> {
+        self.inner.body = body;
+
+        // The total timeout covers every attempt, along with the waits
+        let response = if let Some(timeout) = self.inner.timeouts.total {
+            tokio::time::timeout(timeout, self.attempts())
+                .await
+                .map_err(|_| Error::Timeout)??
+        } else {
+            self.attempts().await?
+        };
+
+        check_error(response, self.endpoint, &self.inner.url).map(Response)
+    }
+
+    /// Sends the request, then again on transient failures if retrying
+    async fn attempts(&self) -> Result<HttpResponse, Error> {
+        let mut attempt = 1;
+        loop {
+            let outcome = self.transport.send(self.inner.clone()).await;
+            if !self.retrying {
+                return outcome;
+            }
+            let policy = &self.retry_policy;
+            let delay = match &outcome {
+                Ok(response) if policy.is_retryable_status(response.status) => {
+                    policy.delay(attempt, response.header("Retry-After"))
+                }
+                Err(Error::Network(_) | Error::Timeout)
+                    if policy.retry_network_errors =>
+                {
+                    policy.delay(attempt, None)
+                }
+                _ => None,
+            };
+            match delay {
+                Some(delay) => {
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                None => return outcome,
+            }
+        }
     }
 
     pub(crate) async fn send(self) -> Result<Response, Error> {
-        self.body(Body::default()).await
+        self.body(vec![]).await
+    }
+
+    /// Sends the request, handing the body of a successful response over to
+    /// `sink` as it arrives. Never retried, as the body might be half-sunk.
+    pub(crate) async fn stream(
+        self,
+        sink: &mut BodySink<'_>,
+    ) -> Result<Response, Error> {
+        let exchange = self.transport.send_streaming(self.inner.clone(), sink);
+        let response = if let Some(timeout) = self.inner.timeouts.total {
+            tokio::time::timeout(timeout, exchange)
+                .await
+                .map_err(|_| Error::Timeout)??
+        } else {
+            exchange.await?
+        };
+        check_error(response, self.endpoint, &self.inner.url).map(Response)
     }
 
     pub(crate) async fn send_serializable<T: Serialize>(
         self,
         data: &T,
     ) -> Result<Response, Error> {
-        let json = serde_json::to_string(data)
-            .map_err(|err| Error::Serialization(err))?;
-        let body = Body::from(json);
-        self.body(body).await
+        let json = serde_json::to_vec(data).map_err(Error::Serialization)?;
+        self.body(json).await
     }
 }
 
-pub(crate) struct Response(HyperResponse<Body>);
+pub(crate) struct Response(HttpResponse);
 
 impl Response {
-    pub(crate) async fn to_vec(self) -> Result<Vec<u8>, Error> {
-        let data = hyper::body::to_bytes(self.0)
-            .await
-            .map_err(|_err| Error::Generic)?
-            .to_vec();
-        Ok(data)
+    pub(crate) fn to_vec(self) -> Vec<u8> {
+        self.0.body
     }
 
-    pub(crate) async fn deserialize<T: DeserializeOwned>(
-        mut self,
-    ) -> Result<T, Error> {
-        let body = hyper::body::aggregate(&mut self.0)
-            .await
-            .map_err(|_err| Error::Generic)?;
-
-        let data: T = serde_json::from_reader(body.reader())
-            .map_err(|err| Error::Deserialization(err))?;
+    pub(crate) fn deserialize<T: DeserializeOwned>(self) -> Result<T, Error> {
+        let data: T = serde_json::from_slice(&self.0.body)
+            .map_err(Error::Deserialization)?;
         Ok(data)
     }
-
-    pub(crate) fn code(&self) -> StatusCode {
-        self.0.status()
-    }
 }
 
-pub(crate) fn check_error(code: StatusCode) -> Result<(), Error> {
-    let ucode = code.as_u16();
+/// Turns responses with error status codes into the matching error
+pub(crate) fn check_error(
+    response: HttpResponse,
+    endpoint: Option<Endpoint>,
+    url: &str,
+) -> Result<HttpResponse, Error> {
+    let code =
+        StatusCode::from_u16(response.status).map_err(|_| Error::Decode)?;
+    if code.is_success() || code.is_informational() {
+        return Ok(response);
+    }
 
-    if code.is_server_error() {
-        Err(Error::Server)
-    } else if ucode == 404 {
-        Err(Error::ResourceMissing)
+    let detail = serde_json::from_slice::<nmodels::ErrorDetail>(&response.body)
+        .ok()
+        .map(|body| body.detail);
+    let err = HttpError {
+        status: response.status,
+        endpoint,
+        url: url.to_string(),
+        detail,
+        body: response.body,
+    };
+
+    Err(if code.is_server_error() {
+        Error::Server(err)
+    } else if code == StatusCode::UNAUTHORIZED {
+        Error::Authentication(Some(err))
+    } else if code == StatusCode::FORBIDDEN {
+        Error::PermissionDenied(err)
+    } else if code == StatusCode::NOT_FOUND {
+        Error::ResourceMissing(err)
     } else if code.is_client_error() {
-        Err(Error::Client)
-    } else if code.is_redirection() {
-        Err(Error::ResourceMissing)
+        Error::Client(err)
     } else {
-        Ok(())
-    }
+        Error::Redirection(err)
+    })
 }
@@ -3,4 +3,6 @@ pub(crate) mod endpoints;
 pub(crate) mod http;
 mod model_conversion;
 pub(crate) mod models;
+pub(crate) mod retry;
 mod tests;
+pub(crate) mod transport;
@@ -1,10 +1,11 @@
 use crate::coersion::ObjRef;
+use crate::hours::{OpeningHours, OpeningPeriod};
 use crate::keys::*;
+use crate::models;
 use crate::models::ShiftType;
 use crate::network::models as nmodels;
 use crate::network::models::RoomType;
 use crate::nmodels::{GroupActivity, GroupEventType, GroupScheduling, GroupType, GroupVisibility};
-use crate::{models, UPSTREAM};
 use crate::{ShiftKey, Supernova};
 use std::cell::Cell;
 use std::sync::Arc;
@@ -18,11 +19,11 @@ impl nmodels::Building {
             thumb: self
                 .thumb
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
+                .map(|url| format!("{}{}", client.upstream(), url)),
             cover: self
                 .cover
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
+                .map(|url| format!("{}{}", client.upstream(), url)),
             places: self
                 .places
                 .iter()
@@ -52,7 +53,7 @@ impl nmodels::Place {
             cover: self
                 .cover
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
+                .map(|url| format!("{}{}", client.upstream(), url)),
             variant: if let Some(meta) = &self.room_meta {
                 models::PlaceVariant::Room(models::Room {
                     title: meta.title.clone(),
@@ -65,7 +66,7 @@ impl nmodels::Place {
                     description: meta.description.clone(),
                     equipment: meta.equipment.clone(),
                     extinguished: meta.extinguished.clone(),
-                    url: format!("{}{}", *UPSTREAM, meta.url),
+                    url: format!("{}{}", client.upstream(), meta.url),
                 })
             } else {
                 models::PlaceVariant::Generic
@@ -103,7 +104,7 @@ impl nmodels::Course {
             degree: models::Degree::from(self.degree),
             description: self.description.clone(),
             active: self.active,
-            url: format!("{}{}", *UPSTREAM, self.url),
+            url: format!("{}{}", client.upstream(), self.url),
             external_url: self.external_url.clone(),
             coordinator: self
                 .coordinator
@@ -158,7 +159,121 @@ impl nmodels::ClassInstance {
                 .collect(),
             department: self
                 .department
-                .map(|key| ObjRef::<models::Department, DepartmentKey>::new(key, client)),
+                .map(|key| ObjRef::<models::Department, DepartmentKey>::new(key, client.clone())),
+            parent: ObjRef::<models::Class, ClassKey>::new(self.parent, client.clone()),
+            files: ObjRef::<models::ClassInstanceFiles, ClassInstanceKey>::new(
+                self.id,
+                client.clone(),
+            ),
+            events: ObjRef::<Vec<models::ClassEvent>, ClassInstanceKey>::new(self.id, client),
+        }
+    }
+}
+
+impl nmodels::ClassInstanceFiles {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::ClassInstanceFiles {
+        models::ClassInstanceFiles {
+            official: self.official.iter().map(|file| file.link(client)).collect(),
+            community: self
+                .community
+                .iter()
+                .map(|file| file.link(client))
+                .collect(),
+        }
+    }
+}
+
+impl nmodels::ClassInstanceFile {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::ClassInstanceFile {
+        models::ClassInstanceFile {
+            id: self.id,
+            file: models::File {
+                hash: self.file.hash.clone(),
+                size: self.file.size,
+                mime: self.file.mime.clone(),
+                license: self.file.license.clone(),
+                url: format!("{}{}", client.upstream(), self.file.url),
+            },
+            name: self.name.clone(),
+            category: self.category.into(),
+            upload_datetime: self.upload_datetime.clone(),
+            uploader: self.uploader,
+            uploader_teacher: self.uploader_teacher,
+            url: format!("{}{}", client.upstream(), self.url),
+        }
+    }
+}
+
+impl nmodels::ClassEvent {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::ClassEvent {
+        models::ClassEvent {
+            id: self.id,
+            event_type: self.event_type.into(),
+            season: self.season.map(models::Season::from),
+            date: self.date,
+            time: self.time,
+            duration: self.duration,
+            visibility: self.visibility.into(),
+            description: self.description.clone(),
+            place: self
+                .place
+                .map(|key| ObjRef::<models::Place, PlaceKey>::new(key, client.clone())),
+        }
+    }
+}
+
+impl From<nmodels::ClassEventType> for models::ClassEventType {
+    fn from(event_type: nmodels::ClassEventType) -> Self {
+        match event_type {
+            nmodels::ClassEventType::Test => models::ClassEventType::Test,
+            nmodels::ClassEventType::Exam => models::ClassEventType::Exam,
+            nmodels::ClassEventType::Discussion => models::ClassEventType::Discussion,
+            nmodels::ClassEventType::FieldTrip => models::ClassEventType::FieldTrip,
+            nmodels::ClassEventType::ProjectAnnouncement => {
+                models::ClassEventType::ProjectAnnouncement
+            }
+            nmodels::ClassEventType::ProjectDelivery => models::ClassEventType::ProjectDelivery,
+            nmodels::ClassEventType::AdditionalClass => models::ClassEventType::AdditionalClass,
+            nmodels::ClassEventType::Presentation => models::ClassEventType::Presentation,
+            nmodels::ClassEventType::Seminar => models::ClassEventType::Seminar,
+            nmodels::ClassEventType::Talk => models::ClassEventType::Talk,
+        }
+    }
+}
+
+impl From<nmodels::Season> for models::Season {
+    fn from(season: nmodels::Season) -> Self {
+        match season {
+            nmodels::Season::Normal => models::Season::Normal,
+            nmodels::Season::Exam => models::Season::Exam,
+            nmodels::Season::Special => models::Season::Special,
+        }
+    }
+}
+
+impl From<nmodels::FileVisibility> for models::FileVisibility {
+    fn from(visibility: nmodels::FileVisibility) -> Self {
+        match visibility {
+            nmodels::FileVisibility::Public => models::FileVisibility::Public,
+            nmodels::FileVisibility::Students => models::FileVisibility::Students,
+            nmodels::FileVisibility::Enrolled => models::FileVisibility::Enrolled,
+            nmodels::FileVisibility::Nobody => models::FileVisibility::Nobody,
+        }
+    }
+}
+
+impl From<nmodels::FileCategory> for models::FileCategory {
+    fn from(category: nmodels::FileCategory) -> Self {
+        match category {
+            nmodels::FileCategory::Image => models::FileCategory::Image,
+            nmodels::FileCategory::Slides => models::FileCategory::Slides,
+            nmodels::FileCategory::Problems => models::FileCategory::Problems,
+            nmodels::FileCategory::Protocol => models::FileCategory::Protocol,
+            nmodels::FileCategory::Seminar => models::FileCategory::Seminar,
+            nmodels::FileCategory::Exam => models::FileCategory::Exam,
+            nmodels::FileCategory::Test => models::FileCategory::Test,
+            nmodels::FileCategory::Support => models::FileCategory::Support,
+            nmodels::FileCategory::Others => models::FileCategory::Others,
         }
     }
 }
@@ -182,11 +297,11 @@ impl nmodels::Student {
                 .collect(),
             first_year: self.first_year,
             last_year: self.last_year,
+            avg_grade: self.avg_grade,
+            url: format!("{}{}", client.upstream(), self.url),
             course: self
                 .course
                 .map(|key| ObjRef::<models::Course, CourseKey>::new(key, client)),
-            avg_grade: self.avg_grade,
-            url: format!("{}{}", *UPSTREAM, self.url),
         }
     }
 }
@@ -204,7 +319,7 @@ impl nmodels::Teacher {
             thumb: self
                 .thumb
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
+                .map(|url| format!("{}{}", client.upstream(), url)),
             rank: self.rank.clone(),
             departments: self
                 .departments
@@ -216,7 +331,7 @@ impl nmodels::Teacher {
                 .iter()
                 .map(|key| ObjRef::<models::ClassShift, ShiftKey>::new(*key, client.clone()))
                 .collect(),
-            url: format!("{}{}", *UPSTREAM, self.url),
+            url: format!("{}{}", client.upstream(), self.url),
             client,
             thumb_cache: once_cell::sync::OnceCell::default(),
         }
@@ -249,7 +364,11 @@ impl nmodels::Enrollment {
 }
 
 impl nmodels::ClassShift {
-    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::ClassShift {
+    pub(crate) fn link(
+        &self,
+        client: &Arc<Supernova>,
+        class_instance: Option<ClassInstanceKey>,
+    ) -> models::ClassShift {
         models::ClassShift {
             id: self.id,
             number: self.number,
@@ -264,6 +383,9 @@ impl nmodels::ClassShift {
                 .iter()
                 .map(|shift_inst| shift_inst.link(client.clone()))
                 .collect(),
+            class_instance: class_instance.map(|key| {
+                ObjRef::<models::ClassInstance, ClassInstanceKey>::new(key, client.clone())
+            }),
         }
     }
 }
@@ -313,9 +435,9 @@ impl From<nmodels::Weekday> for models::Weekday {
     fn from(weekday: nmodels::Weekday) -> Self {
         match weekday {
             nmodels::Weekday::Monday => models::Weekday::Monday,
-            nmodels::Weekday::Thursday => models::Weekday::Thursday,
-            nmodels::Weekday::Wednesday => models::Weekday::Wednesday,
             nmodels::Weekday::Tuesday => models::Weekday::Tuesday,
+            nmodels::Weekday::Wednesday => models::Weekday::Wednesday,
+            nmodels::Weekday::Thursday => models::Weekday::Thursday,
             nmodels::Weekday::Friday => models::Weekday::Friday,
             nmodels::Weekday::Saturday => models::Weekday::Saturday,
             nmodels::Weekday::Sunday => models::Weekday::Sunday,
@@ -381,9 +503,49 @@ impl nmodels::PlaceFeature {
 // ------------ Users ---------------
 
 impl nmodels::User {
-    #[allow(dead_code)] // TODO
-    pub(crate) fn to_model(&self) -> models::User {
-        models::User { id: 0 }
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::User {
+        models::User {
+            id: self.id,
+            nickname: self.nickname.clone(),
+            name: self.name.clone(),
+            picture: self
+                .picture
+                .as_ref()
+                .map(|url| format!("{}{}", client.upstream(), url)),
+            social_links: self
+                .social_links
+                .iter()
+                .map(|link| models::SocialLink {
+                    network: link.network.clone(),
+                    url: link.url.clone(),
+                })
+                .collect(),
+            url: format!("{}{}", client.upstream(), self.url),
+            students: self
+                .students
+                .iter()
+                .map(|key| ObjRef::<models::Student, StudentKey>::new(*key, client.clone()))
+                .collect(),
+            teacher: self
+                .teacher
+                .map(|key| ObjRef::<models::Teacher, TeacherKey>::new(key, client.clone())),
+            groups: self
+                .groups
+                .iter()
+                .map(|key| ObjRef::<models::Group, GroupKey>::new(*key, client.clone()))
+                .collect(),
+            client: client.clone(),
+            picture_cache: once_cell::sync::OnceCell::default(),
+        }
+    }
+}
+
+impl nmodels::CurrentUser {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::CurrentUser {
+        models::CurrentUser {
+            profile: self.user.link(client),
+            permissions: self.permissions.clone(),
+        }
     }
 }
 
@@ -395,11 +557,11 @@ impl nmodels::WeakGroup {
             id: self.id,
             name: self.name.clone(),
             abbreviation: self.abbreviation.clone(),
-            url: format!("{}{}", *UPSTREAM, self.url),
+            url: format!("{}{}", client.upstream(), self.url),
             thumb: self
                 .thumb
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
+                .map(|url| format!("{}{}", client.upstream(), url)),
             group_type: self.group_type.into(),
             official: self.official,
             upgraded: Cell::new(false),
@@ -420,11 +582,11 @@ impl nmodels::Group {
             id: self.id,
             name: self.name.clone(),
             abbreviation: self.abbreviation.clone(),
-            url: format!("{}{}", *UPSTREAM, self.url),
+            url: format!("{}{}", client.upstream(), self.url),
             thumb: self
                 .thumb
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
+                .map(|url| format!("{}{}", client.upstream(), url)),
             group_type: self.group_type.into(),
             official: self.official,
             upgraded: Cell::new(true),
@@ -448,12 +610,9 @@ impl nmodels::Group {
                 .map(nmodels::GroupScheduling::to_model)
                 .collect(),
         );
-        group.events.set(
-            self.events
-                .iter()
-                .map(|event| event.link(client.clone()))
-                .collect(),
-        );
+        group
+            .events
+            .set(self.events.iter().map(|event| event.link(client)).collect());
         group
     }
 }
@@ -474,7 +633,7 @@ impl nmodels::GroupActivity {
 }
 
 impl nmodels::Event {
-    pub(crate) fn link(&self, client: Arc<Supernova>) -> models::Event {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::Event {
         models::Event {
             id: self.id,
             title: self.title.clone(),
@@ -483,10 +642,34 @@ impl nmodels::Event {
             duration: self.duration,
             place: self
                 .place
-                .map(|key| ObjRef::<models::Place, PlaceKey>::new(key, client)),
+                .map(|key| ObjRef::<models::Place, PlaceKey>::new(key, client.clone())),
             capacity: self.capacity,
             cost: self.cost,
             event_type: self.event_type.into(),
+            group: self
+                .group
+                .map(|key| ObjRef::<models::Group, GroupKey>::new(key, client.clone())),
+            schedule: self
+                .schedule
+                .iter()
+                .map(|session| models::EventSession {
+                    start: session.start,
+                    end: session.end,
+                    place: session
+                        .place
+                        .map(|key| ObjRef::<models::Place, PlaceKey>::new(key, client.clone())),
+                })
+                .collect(),
+            enrollment: self
+                .enrollment
+                .as_ref()
+                .map(|enrollment| models::EventEnrollment {
+                    required: enrollment.required,
+                    open: enrollment.open,
+                    deadline: enrollment.deadline,
+                    enrolled: enrollment.enrolled,
+                    url: enrollment.url.clone(),
+                }),
         }
     }
 }
@@ -585,7 +768,7 @@ impl nmodels::EventsPage {
             items: self
                 .results
                 .iter()
-                .map(|item| Arc::new(item.link(client.clone())))
+                .map(|item| Arc::new(item.link(client)))
                 .collect(),
         })
     }
@@ -660,10 +843,265 @@ impl nmodels::NewsItem {
             thumb: self
                 .thumb
                 .as_ref()
-                .map(|url| format!("{}{}", *UPSTREAM, url)),
-            url: format!("{}{}", *UPSTREAM, self.url),
+                .map(|url| format!("{}{}", client.upstream(), url)),
+            url: format!("{}{}", client.upstream(), self.url),
             client,
             thumb_cache: once_cell::sync::OnceCell::default(),
         }
     }
 }
+
+// ------------ Learning --------------
+
+impl nmodels::LearningArea {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::LearningArea {
+        models::LearningArea {
+            id: self.id,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            subareas: self
+                .subareas
+                .iter()
+                .map(|key| ObjRef::new(*key, client.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl nmodels::LearningSubarea {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::LearningSubarea {
+        models::LearningSubarea {
+            id: self.id,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            area: ObjRef::new(self.area, client.clone()),
+            sections: self
+                .sections
+                .iter()
+                .map(|key| ObjRef::new(*key, client.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl nmodels::LearningSection {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::LearningSection {
+        models::LearningSection {
+            id: self.id,
+            title: self.title.clone(),
+            description: self.description.clone(),
+            content: self.content.clone(),
+            subarea: self.subarea.map(|key| ObjRef::new(key, client.clone())),
+            parents: self
+                .parents
+                .iter()
+                .map(|key| ObjRef::new(*key, client.clone()))
+                .collect(),
+            children: self
+                .children
+                .iter()
+                .map(|key| ObjRef::new(*key, client.clone()))
+                .collect(),
+            questions: self
+                .questions
+                .iter()
+                .map(|key| ObjRef::new(*key, client.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl nmodels::Question {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::Question {
+        models::Question {
+            id: self.id,
+            title: self.title.clone(),
+            content: self.content.clone(),
+            timestamp: self.timestamp,
+            answers: self
+                .answers
+                .iter()
+                .map(|answer| answer.link(client))
+                .collect(),
+            author: self.user.map(|key| ObjRef::new(key, client.clone())),
+            sections: self
+                .sections
+                .iter()
+                .map(|key| ObjRef::new(*key, client.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl nmodels::QuestionAnswer {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::QuestionAnswer {
+        models::QuestionAnswer {
+            id: self.id,
+            content: self.content.clone(),
+            timestamp: self.timestamp,
+            accepted: self.accepted,
+            author: self.user.map(|key| ObjRef::new(key, client.clone())),
+        }
+    }
+}
+
+// ------------ Transportation --------------
+
+impl nmodels::TransportationDay {
+    pub(crate) fn to_model(&self) -> models::TransportationDay {
+        models::TransportationDay {
+            date: self.date,
+            lines: self
+                .lines
+                .iter()
+                .map(nmodels::TransportationLine::to_model)
+                .collect(),
+        }
+    }
+}
+
+impl nmodels::TransportationLine {
+    pub(crate) fn to_model(&self) -> models::TransportationLine {
+        models::TransportationLine {
+            id: self.id,
+            name: self.name.clone(),
+            mode: self.mode.into(),
+            stops: self
+                .stops
+                .iter()
+                .map(|stop| models::TransportationStop {
+                    id: stop.id,
+                    name: stop.name.clone(),
+                })
+                .collect(),
+            trips: self
+                .trips
+                .iter()
+                .map(|trip| models::TransportationTrip {
+                    destination: trip.destination.clone(),
+                    stops: trip
+                        .stops
+                        .iter()
+                        .map(|stop| (stop.stop, stop.time))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<nmodels::TransportationMode> for models::TransportationMode {
+    fn from(mode: nmodels::TransportationMode) -> Self {
+        match mode {
+            nmodels::TransportationMode::Bus => models::TransportationMode::Bus,
+            nmodels::TransportationMode::Ferry => models::TransportationMode::Ferry,
+            nmodels::TransportationMode::Train => models::TransportationMode::Train,
+            nmodels::TransportationMode::Metro => models::TransportationMode::Metro,
+            nmodels::TransportationMode::Other => models::TransportationMode::Other,
+        }
+    }
+}
+
+// ------------ Weather --------------
+
+impl nmodels::Weather {
+    pub(crate) fn to_model(&self) -> models::Weather {
+        let current = &self.current;
+        models::Weather {
+            current: models::CurrentWeather {
+                time: current.time,
+                temperature: current.temperature,
+                humidity: current.humidity,
+                precipitation: current.precipitation,
+                wind_speed: current.wind_speed,
+                wind_direction: current.wind_direction,
+                condition: models::WeatherCondition::from_code(current.condition),
+            },
+            hourly: self
+                .hourly
+                .iter()
+                .map(|forecast| models::HourlyForecast {
+                    time: forecast.time,
+                    temperature: forecast.temperature,
+                    precipitation: forecast.precipitation,
+                    precipitation_probability: forecast.precipitation_probability,
+                    wind_speed: forecast.wind_speed,
+                    wind_direction: forecast.wind_direction,
+                    condition: models::WeatherCondition::from_code(forecast.condition),
+                })
+                .collect(),
+            daily: self
+                .daily
+                .iter()
+                .map(|forecast| models::DailyForecast {
+                    date: forecast.date,
+                    min_temperature: forecast.min_temperature,
+                    max_temperature: forecast.max_temperature,
+                    precipitation: forecast.precipitation,
+                    precipitation_probability: forecast.precipitation_probability,
+                    wind_speed: forecast.wind_speed,
+                    condition: models::WeatherCondition::from_code(forecast.condition),
+                })
+                .collect(),
+        }
+    }
+}
+
+// ------------ Services --------------
+
+impl nmodels::Service {
+    pub(crate) fn link(&self, client: &Arc<Supernova>) -> models::Service {
+        models::Service {
+            id: self.id,
+            name: self.name.clone(),
+            service_type: self.service_type.into(),
+            description: self.description.clone(),
+            opening_hours: OpeningHours::weekly(
+                self.opening_hours
+                    .iter()
+                    .map(|hours| OpeningPeriod {
+                        weekday: hours.weekday.into(),
+                        start: hours.start,
+                        end: hours.end,
+                    })
+                    .collect(),
+            ),
+            menu: self
+                .menu
+                .as_ref()
+                .map(|menu| menu.iter().map(nmodels::MenuDay::to_model).collect()),
+            place: self.place.map(|key| ObjRef::new(key, client.clone())),
+        }
+    }
+}
+
+impl nmodels::MenuDay {
+    pub(crate) fn to_model(&self) -> models::MenuDay {
+        models::MenuDay {
+            date: self.date,
+            items: self
+                .items
+                .iter()
+                .map(|item| models::MenuItem {
+                    name: item.name.clone(),
+                    category: item.category.clone(),
+                    price: item.price,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<nmodels::ServiceType> for models::ServiceType {
+    fn from(service_type: nmodels::ServiceType) -> Self {
+        match service_type {
+            nmodels::ServiceType::Canteen => models::ServiceType::Canteen,
+            nmodels::ServiceType::Cafeteria => models::ServiceType::Cafeteria,
+            nmodels::ServiceType::Bar => models::ServiceType::Bar,
+            nmodels::ServiceType::Library => models::ServiceType::Library,
+            nmodels::ServiceType::Reprography => models::ServiceType::Reprography,
+            nmodels::ServiceType::Store => models::ServiceType::Store,
+            nmodels::ServiceType::Other => models::ServiceType::Other,
+        }
+    }
+}
@@ -41,13 +41,19 @@ pub(crate) struct TokenResult {
     pub(crate) token: AuthToken,
 }
 
+/// The body of most error responses
+#[derive(Deserialize)]
+pub(crate) struct ErrorDetail {
+    pub(crate) detail: String,
+}
+
 #[derive(Deserialize_repr, Debug, PartialEq, Copy, Clone)]
 #[repr(u8)]
 pub(crate) enum Weekday {
     Monday = 0,
-    Thursday = 1,
+    Tuesday = 1,
     Wednesday = 2,
-    Tuesday = 3,
+    Thursday = 3,
     Friday = 4,
     Saturday = 5,
     Sunday = 6,
@@ -337,6 +343,20 @@ pub(crate) struct ClassShiftInstance {
     pub(crate) room: Option<RoomKey>,
 }
 
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct ClassEvent {
+    pub(crate) id: ClassEventKey,
+    #[serde(rename = "type")]
+    pub(crate) event_type: ClassEventType,
+    pub(crate) season: Option<Season>,
+    pub(crate) date: NaiveDate,
+    pub(crate) time: Option<NaiveTime>,
+    pub(crate) duration: Option<u16>,
+    pub(crate) place: Option<PlaceKey>,
+    pub(crate) visibility: FileVisibility,
+    pub(crate) description: Option<String>,
+}
+
 #[derive(Deserialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "snake_case")]
 pub(crate) struct ClassInstanceFiles {
@@ -424,9 +444,30 @@ pub(crate) struct Enrollment {
 
 // ------------ Users ---------------
 
-#[derive(Deserialize, Debug, PartialEq, Copy, Clone)]
-pub struct User {
-    pub id: UserKey,
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct User {
+    pub(crate) id: UserKey,
+    pub(crate) nickname: String,
+    pub(crate) name: String,
+    pub(crate) picture: Option<String>,
+    pub(crate) students: Vec<StudentKey>,
+    pub(crate) teacher: Option<TeacherKey>,
+    pub(crate) groups: Vec<GroupKey>,
+    pub(crate) social_links: Vec<SocialLink>,
+    pub(crate) url: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct CurrentUser {
+    #[serde(flatten)]
+    pub(crate) user: User,
+    pub(crate) permissions: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct SocialLink {
+    pub(crate) network: String,
+    pub(crate) url: String,
 }
 
 // --------- Groups ----------
@@ -565,6 +606,26 @@ pub(crate) struct Event {
     pub(crate) cost: Option<u32>,
     #[serde(rename = "type")]
     pub(crate) event_type: GroupEventType,
+    pub(crate) group: Option<GroupKey>,
+    #[serde(default)]
+    pub(crate) schedule: Vec<EventSession>,
+    pub(crate) enrollment: Option<EventEnrollment>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct EventSession {
+    pub(crate) start: DateTime<Utc>,
+    pub(crate) end: DateTime<Utc>,
+    pub(crate) place: Option<PlaceKey>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct EventEnrollment {
+    pub(crate) required: bool,
+    pub(crate) open: bool,
+    pub(crate) deadline: Option<DateTime<Utc>>,
+    pub(crate) enrolled: u32,
+    pub(crate) url: Option<String>,
 }
 
 #[derive(Deserialize, Debug, PartialEq, Clone)]
@@ -594,3 +655,191 @@ pub(crate) struct NewsItem {
     pub(crate) thumb: Option<String>,
     pub(crate) url: String,
 }
+
+// ------------ Learning --------------
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct LearningArea {
+    pub(crate) id: LearningAreaKey,
+    pub(crate) title: String,
+    pub(crate) description: Option<String>,
+    pub(crate) subareas: Vec<LearningSubareaKey>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct LearningSubarea {
+    pub(crate) id: LearningSubareaKey,
+    pub(crate) title: String,
+    pub(crate) description: Option<String>,
+    pub(crate) area: LearningAreaKey,
+    pub(crate) sections: Vec<LearningSectionKey>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct LearningSection {
+    pub(crate) id: LearningSectionKey,
+    pub(crate) title: String,
+    pub(crate) description: Option<String>,
+    pub(crate) content: Option<String>,
+    pub(crate) subarea: Option<LearningSubareaKey>,
+    pub(crate) parents: Vec<LearningSectionKey>,
+    pub(crate) children: Vec<LearningSectionKey>,
+    pub(crate) questions: Vec<QuestionKey>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct Question {
+    pub(crate) id: QuestionKey,
+    pub(crate) title: String,
+    pub(crate) content: String,
+    pub(crate) user: Option<UserKey>,
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) sections: Vec<LearningSectionKey>,
+    pub(crate) answers: Vec<QuestionAnswer>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct QuestionAnswer {
+    pub(crate) id: u32,
+    pub(crate) content: String,
+    pub(crate) user: Option<UserKey>,
+    pub(crate) timestamp: DateTime<Utc>,
+    pub(crate) accepted: bool,
+}
+
+// ------------ Transportation --------------
+
+#[derive(Deserialize, Debug, PartialEq, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum TransportationMode {
+    Bus,
+    Ferry,
+    Train,
+    Metro,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct TransportationDay {
+    pub(crate) date: NaiveDate,
+    pub(crate) lines: Vec<TransportationLine>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct TransportationLine {
+    pub(crate) id: TransportationLineKey,
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) mode: TransportationMode,
+    pub(crate) stops: Vec<TransportationStop>,
+    pub(crate) trips: Vec<TransportationTrip>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct TransportationStop {
+    pub(crate) id: TransportationStopKey,
+    pub(crate) name: String,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct TransportationTrip {
+    pub(crate) destination: Option<String>,
+    pub(crate) stops: Vec<TransportationTripStop>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct TransportationTripStop {
+    pub(crate) stop: TransportationStopKey,
+    pub(crate) time: NaiveTime,
+}
+
+// ------------ Weather --------------
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct Weather {
+    pub(crate) current: CurrentWeather,
+    pub(crate) hourly: Vec<HourlyForecast>,
+    pub(crate) daily: Vec<DailyForecast>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct CurrentWeather {
+    pub(crate) time: DateTime<Utc>,
+    pub(crate) temperature: f32,
+    pub(crate) humidity: Option<u8>,
+    pub(crate) precipitation: f32,
+    pub(crate) wind_speed: f32,
+    pub(crate) wind_direction: Option<u16>,
+    pub(crate) condition: u8,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct HourlyForecast {
+    pub(crate) time: DateTime<Utc>,
+    pub(crate) temperature: f32,
+    pub(crate) precipitation: f32,
+    pub(crate) precipitation_probability: Option<u8>,
+    pub(crate) wind_speed: f32,
+    pub(crate) wind_direction: Option<u16>,
+    pub(crate) condition: u8,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct DailyForecast {
+    pub(crate) date: NaiveDate,
+    pub(crate) min_temperature: f32,
+    pub(crate) max_temperature: f32,
+    pub(crate) precipitation: f32,
+    pub(crate) precipitation_probability: Option<u8>,
+    pub(crate) wind_speed: f32,
+    pub(crate) condition: u8,
+}
+
+// ------------ Services --------------
+
+#[derive(Deserialize, Debug, PartialEq, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ServiceType {
+    Canteen,
+    Cafeteria,
+    Bar,
+    Library,
+    Reprography,
+    Store,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct Service {
+    pub(crate) id: ServiceKey,
+    pub(crate) name: String,
+    #[serde(rename = "type")]
+    pub(crate) service_type: ServiceType,
+    pub(crate) description: Option<String>,
+    pub(crate) place: Option<PlaceKey>,
+    pub(crate) opening_hours: Vec<ServiceOpeningHours>,
+    /// Only sent along with the details of a service
+    pub(crate) menu: Option<Vec<MenuDay>>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct ServiceOpeningHours {
+    pub(crate) weekday: Weekday,
+    pub(crate) start: NaiveTime,
+    pub(crate) end: NaiveTime,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct MenuDay {
+    pub(crate) date: NaiveDate,
+    pub(crate) items: Vec<MenuItem>,
+}
+
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub(crate) struct MenuItem {
+    pub(crate) name: String,
+    pub(crate) category: Option<String>,
+    pub(crate) price: Option<f32>,
+}
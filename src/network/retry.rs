@@ -0,0 +1,121 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, SystemTime};
+
+use chrono::{DateTime, Utc};
+
+/// How idempotent requests are retried when they fail transiently.
+///
+/// Retries happen after an exponentially growing delay
+/// (`initial_backoff * multiplier ^ retry`), capped at `max_backoff`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total amount of attempts, including the first one.
+    /// One (or zero) disables retries.
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: u32,
+    /// Randomizes each delay to be between half and the whole of its value,
+    /// so that several clients don't retry in lockstep.
+    pub jitter: bool,
+    /// Response status codes that warrant another attempt
+    pub retryable_statuses: Vec<u16>,
+    /// Whether to retry when no response could be obtained at all
+    pub retry_network_errors: bool,
+    /// Whether to wait for as long as a `Retry-After` header asks instead of
+    /// the computed backoff. Requests asking for waits longer than
+    /// `max_backoff` are not retried.
+    pub respect_retry_after: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2,
+            jitter: true,
+            retryable_statuses: vec![408, 429, 502, 503, 504],
+            retry_network_errors: true,
+            respect_retry_after: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy under which nothing is retried
+    #[must_use]
+    pub fn disabled() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 1,
+            ..RetryPolicy::default()
+        }
+    }
+
+    pub(crate) fn is_retryable_status(&self, status: u16) -> bool {
+        self.retryable_statuses.contains(&status)
+    }
+
+    /// The delay before the attempt that follows the `attempt`th one,
+    /// or `None` if there should be no further attempt.
+    pub(crate) fn delay(
+        &self,
+        attempt: u32,
+        retry_after: Option<&str>,
+    ) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        if self.respect_retry_after {
+            if let Some(requested) = retry_after.and_then(parse_retry_after) {
+                return if requested <= self.max_backoff {
+                    Some(requested)
+                } else {
+                    None
+                };
+            }
+        }
+
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(self.multiplier.saturating_pow(attempt - 1))
+            .min(self.max_backoff);
+
+        Some(if self.jitter {
+            let half = backoff / 2;
+            half + half.mul_f64(f64::from(random_permille()) / 1000.0)
+        } else {
+            backoff
+        })
+    }
+}
+
+/// Parses the value of a `Retry-After` header,
+/// which is either an amount of seconds or an HTTP date.
+pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    let now = DateTime::<Utc>::from(SystemTime::now());
+    Some(
+        date.signed_duration_since(now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// A number between 0 and 1000, good enough to spread retries apart
+fn random_permille() -> u32 {
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(elapsed) =
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH)
+    {
+        hasher.write_u32(elapsed.subsec_nanos());
+    }
+    u32::try_from(hasher.finish() % 1001).unwrap_or(1000)
+}
@@ -0,0 +1,123 @@
+use crate::academic::{AcademicCalendar, Term};
+use crate::models::{Period, Season, Weekday};
+
+use chrono::NaiveDate;
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+fn calendar() -> AcademicCalendar {
+    AcademicCalendar::new()
+        .with_term(Term::new(
+            2021,
+            Period::Year,
+            date(2021, 9, 13),
+            date(2022, 5, 27),
+        ))
+        .with_term(
+            Term::new(
+                2021,
+                Period::SecondSemester,
+                date(2022, 2, 14),
+                date(2022, 5, 27),
+            )
+            .with_season(Season::Normal, date(2022, 5, 30), date(2022, 6, 17))
+            .with_season(
+                Season::Exam,
+                date(2022, 6, 20),
+                date(2022, 7, 8),
+            ),
+        )
+        // Easter break, along with Freedom Day
+        .with_holidays((11..=15).map(|day| date(2022, 4, day)))
+        .with_holidays([date(2022, 4, 25)])
+}
+
+#[test]
+fn terms_are_found_by_date() {
+    let calendar = calendar();
+    let periods = |date| {
+        calendar
+            .terms_on(date)
+            .iter()
+            .map(|term| term.period)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(periods(date(2021, 10, 1)), vec![Period::Year]);
+    assert_eq!(
+        periods(date(2022, 3, 1)),
+        vec![Period::Year, Period::SecondSemester]
+    );
+    // Evaluations are still part of the term
+    assert_eq!(periods(date(2022, 7, 8)), vec![Period::SecondSemester]);
+    assert!(periods(date(2022, 7, 9)).is_empty());
+
+    let (term, season) = calendar.season_on(date(2022, 6, 20)).unwrap();
+    assert_eq!(term.period, Period::SecondSemester);
+    assert_eq!(season, Season::Exam);
+    assert!(calendar.season_on(date(2022, 5, 27)).is_none());
+}
+
+#[test]
+fn holidays_have_no_lessons() {
+    let calendar = calendar();
+    let semester = (2021, Period::SecondSemester);
+
+    assert!(calendar.is_lesson_day(semester.0, semester.1, date(2022, 4, 18)));
+    assert!(!calendar.is_lesson_day(semester.0, semester.1, date(2022, 4, 25)));
+    assert!(!calendar.is_lesson_day(semester.0, semester.1, date(2022, 6, 1)));
+    assert!(!calendar.is_lesson_day(2020, semester.1, date(2022, 4, 18)));
+
+    let mondays = calendar.lesson_days(semester.0, semester.1, Weekday::Monday);
+    assert_eq!(mondays.first(), Some(&date(2022, 2, 14)));
+    assert_eq!(mondays.last(), Some(&date(2022, 5, 23)));
+    assert!(!mondays.contains(&date(2022, 4, 11)));
+    assert!(!mondays.contains(&date(2022, 4, 25)));
+    assert_eq!(mondays.len(), 13);
+}
+
+#[test]
+fn breaks_are_not_lesson_weeks() {
+    let calendar = calendar();
+    let weeks = calendar.lesson_weeks(2021, Period::SecondSemester);
+
+    // 15 weeks, one of which is the Easter break
+    assert_eq!(weeks.len(), 14);
+    assert_eq!(weeks.first(), Some(&date(2022, 2, 14)));
+    assert!(!weeks.contains(&date(2022, 4, 11)));
+    // Only its Monday is a holiday
+    assert!(weeks.contains(&date(2022, 4, 25)));
+
+    // Terms starting mid-week count from that week's Monday
+    let calendar = AcademicCalendar::new().with_term(Term::new(
+        2022,
+        Period::FirstSemester,
+        date(2022, 9, 14),
+        date(2022, 9, 30),
+    ));
+    assert_eq!(
+        calendar.lesson_weeks(2022, Period::FirstSemester),
+        vec![date(2022, 9, 12), date(2022, 9, 19), date(2022, 9, 26)]
+    );
+}
+
+#[test]
+fn terms_are_replaced() {
+    let calendar = calendar().with_term(Term::new(
+        2021,
+        Period::Year,
+        date(2021, 9, 20),
+        date(2022, 5, 27),
+    ));
+    assert_eq!(calendar.terms.len(), 2);
+    assert_eq!(
+        calendar.term(2021, Period::Year).unwrap().start(),
+        date(2021, 9, 20)
+    );
+    assert_eq!(
+        calendar.term(2021, Period::SecondSemester).unwrap().end(),
+        date(2022, 7, 8)
+    );
+}
@@ -0,0 +1,69 @@
+use crate::errors::Error;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, RetryPolicy, SupernovaBuilder};
+
+use std::sync::{Arc, Mutex};
+
+fn authenticate(transport: &FakeTransport) {
+    transport.respond("/api/validation", 200, r#""Success""#);
+}
+
+#[tokio::test]
+async fn auth_statuses_are_mapped() {
+    let transport = Arc::new(FakeTransport::default());
+    authenticate(&transport);
+    transport.respond("/api/shift/1", 401, r#"{"detail": "Invalid token."}"#);
+    transport.respond("/api/shift/2", 403, "");
+    let client = SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::disabled())
+        .build();
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    let err = client.get_shift(1, &conf).await.unwrap_err();
+    assert!(matches!(err, Error::Authentication(Some(_))));
+    assert_eq!(
+        err.http().unwrap().detail.as_deref(),
+        Some("Invalid token.")
+    );
+    assert!(matches!(
+        client.get_shift(2, &conf).await,
+        Err(Error::PermissionDenied(_))
+    ));
+    // Tokens are kept unless told otherwise
+    assert!(client.is_authenticated());
+}
+
+#[tokio::test]
+async fn invalid_tokens_can_be_cleared() {
+    let transport = Arc::new(FakeTransport::default());
+    authenticate(&transport);
+    transport.respond("/api/shift/1", 401, "");
+    transport.respond("/api/shift/2", 403, "");
+    let invalidated = Arc::new(Mutex::new(vec![]));
+    let client = {
+        let invalidated = invalidated.clone();
+        SupernovaBuilder::new()
+            .transport(transport.clone())
+            .retry_policy(RetryPolicy::disabled())
+            .clear_invalid_tokens(true)
+            .on_token_invalidated(move |token| {
+                invalidated.lock().unwrap().push(token.clone());
+            })
+            .build()
+    };
+    let conf = RequestConfig::default();
+
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    assert!(client.get_shift(2, &conf).await.is_err());
+    assert!(client.is_authenticated());
+
+    assert!(client.get_shift(1, &conf).await.is_err());
+    assert!(!client.is_authenticated());
+    assert_eq!(*invalidated.lock().unwrap(), vec!["abc".to_string()]);
+    assert!(matches!(
+        client.get_shift(1, &conf).await,
+        Err(Error::MissingAuthentication)
+    ));
+}
@@ -0,0 +1,181 @@
+use crate::academic::{AcademicCalendar, Term};
+use crate::calendar::OccurrenceSource;
+use crate::models::Period;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+const ME: &str = r#"
+{
+    "id": 7, "nickname": "claudio", "name": "Cláudio Pereira",
+    "picture": null, "students": [12, 13], "teacher": null, "groups": [3],
+    "social_links": [], "url": "/u/claudio", "permissions": []
+}"#;
+
+fn student(id: u32) -> String {
+    format!(
+        r#"{{
+            "id": {id}, "name": "Cláudio", "abbreviation": null,
+            "number": {id}, "enrollments": [5], "shifts": [1],
+            "first_year": 2020, "last_year": null, "course": null,
+            "avg_grade": null, "url": "/s/{id}"
+        }}"#
+    )
+}
+
+const ENROLLMENT: &str = r#"
+{
+    "id": 5, "class_instance": 24772, "student": 12, "attendance": null,
+    "attendance_date": null, "normal_grade": null,
+    "normal_grade_date": null, "recourse_grade": null,
+    "recourse_grade_date": null, "special_grade": null,
+    "special_grade_date": null, "improvement_grade": null,
+    "improvement_grade_date": null, "approved": null, "grade": null
+}"#;
+
+const CLASS_INSTANCE: &str = r#"
+{
+    "id": 24772, "parent": 449, "period": 3, "year": 2021,
+    "information": {"upstream": null}, "department": null,
+    "avg_grade": null, "enrollments": [],
+    "shifts": [
+        {
+            "id": 1, "number": 2, "type": 2, "teachers": [],
+            "instances": [
+                {"weekday": 0, "start": 540, "duration": 120, "room": null},
+                {"weekday": 2, "start": 840, "duration": 90, "room": null}
+            ]
+        }
+    ],
+    "url": "/faculdade/cadeira/i/24772/"
+}"#;
+
+const SHIFT: &str = r#"
+{
+    "id": 1, "number": 2, "type": 2, "teachers": [],
+    "instances": [
+        {"weekday": 0, "start": 540, "duration": 120, "room": null},
+        {"weekday": 2, "start": 840, "duration": 90, "room": null}
+    ]
+}"#;
+
+const GROUP: &str = r#"
+{
+    "id": 3, "name": "Núcleo de Informática", "abbreviation": "NI",
+    "type": 1, "url": "/g/ni", "thumb": null, "outsiders_openness": 3,
+    "official": true, "activities": [],
+    "schedule_entries": [
+        {
+            "resourcetype": "SchedulePeriodic", "title": "Reunião",
+            "weekday": 3, "time": "18:00:00", "start_date": "2022-05-05",
+            "end_date": "2022-06-30", "duration": 60, "revoked": false
+        },
+        {
+            "resourcetype": "SchedulePeriodic", "title": "Cancelada",
+            "weekday": 4, "time": "18:00:00", "start_date": "2022-01-01",
+            "end_date": "2022-12-31", "duration": 60, "revoked": true
+        },
+        {
+            "resourcetype": "ScheduleOnce", "title": "Assembleia",
+            "datetime": "2022-05-07T15:00:00Z", "duration": 120,
+            "revoked": false
+        }
+    ],
+    "events": []
+}"#;
+
+fn at(date: &str, time: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(&format!("{date} {time}"), "%F %R").unwrap()
+}
+
+#[tokio::test]
+async fn shifts_and_schedulings_are_merged() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/profile/me", 200, ME);
+    transport.respond("/api/student/12", 200, &student(12));
+    transport.respond("/api/student/13", 200, &student(13));
+    transport.respond("/api/enrollment/5", 200, ENROLLMENT);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    transport.respond("/api/shift/1", 200, SHIFT);
+    transport.respond("/api/group/3", 200, GROUP);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+
+    let from = NaiveDate::from_ymd_opt(2022, 5, 2).unwrap();
+    let to = NaiveDate::from_ymd_opt(2022, 5, 9).unwrap();
+    // Lessons are over by the 9th, and the 4th is a holiday
+    let academic = AcademicCalendar::new()
+        .with_term(Term::new(
+            2021,
+            Period::SecondSemester,
+            NaiveDate::from_ymd_opt(2022, 2, 14).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 5, 6).unwrap(),
+        ))
+        .with_holidays([NaiveDate::from_ymd_opt(2022, 5, 4).unwrap()]);
+    let calendar = client
+        .personal_calendar(from, to, &academic, &RequestConfig::default())
+        .await
+        .unwrap();
+
+    // The assembly at 15:00 UTC is at 16:00 in Lisbon's summer time
+    let expected = vec![
+        (at("2022-05-02", "09:00"), at("2022-05-02", "11:00")),
+        (at("2022-05-05", "18:00"), at("2022-05-05", "19:00")),
+        (at("2022-05-07", "16:00"), at("2022-05-07", "18:00")),
+    ];
+    let actual: Vec<_> = calendar
+        .iter()
+        .map(|occurrence| (occurrence.start, occurrence.end))
+        .collect();
+    assert_eq!(actual, expected);
+
+    let OccurrenceSource::Shift { shift, instance } = &calendar[0].source
+    else {
+        panic!("Expected a shift");
+    };
+    assert_eq!(shift.id, 1);
+    assert_eq!(instance.start, 540);
+    let OccurrenceSource::Group {
+        group,
+        group_name,
+        title,
+    } = &calendar[1].source
+    else {
+        panic!("Expected a group scheduling");
+    };
+    assert_eq!(*group, 3);
+    assert_eq!(group_name, "Núcleo de Informática");
+    assert_eq!(title.as_deref(), Some("Reunião"));
+}
+
+#[tokio::test]
+async fn periodic_schedulings_are_bounded() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/group/3", 200, GROUP);
+    let client = Supernova::with_transport(transport);
+
+    let group = client.get_group(3).await.unwrap();
+    let schedulings = group.schedulings().await.unwrap();
+    let from = NaiveDate::from_ymd_opt(2022, 6, 20).unwrap();
+    let to = NaiveDate::from_ymd_opt(2022, 7, 20).unwrap();
+    // Only the 23rd and the 30th of June
+    assert_eq!(schedulings[0].occurrences(from, to).len(), 2);
+    // Revoked
+    assert!(schedulings[1].occurrences(from, to).is_empty());
+    // Before the range
+    assert!(schedulings[2].occurrences(from, to).is_empty());
+}
+
+#[test]
+fn calendar_requests_can_be_spawned() {
+    fn assert_send<T: Send>(_: &T) {}
+    let client = Supernova::new();
+    let date = NaiveDate::from_ymd_opt(2022, 5, 2).unwrap();
+    let conf = RequestConfig::default();
+    let academic = AcademicCalendar::new();
+    assert_send(&client.personal_calendar(date, date, &academic, &conf));
+}
@@ -0,0 +1,62 @@
+use crate::models::{ClassEventType, FileVisibility, Season};
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, NaiveTime};
+
+const CLASS_INSTANCE: &str = r#"
+{
+    "id": 24772, "parent": 449, "period": 3, "year": 2021,
+    "information": {"upstream": null}, "department": null,
+    "avg_grade": null, "enrollments": [], "shifts": [],
+    "url": "/faculdade/cadeira/i/24772/"
+}"#;
+
+const EVENTS: &str = r#"
+[
+    {
+        "id": 2, "type": 6, "season": null, "date": "2022-06-10",
+        "time": null, "duration": null, "place": null, "visibility": 2,
+        "description": "Entrega do projeto"
+    },
+    {
+        "id": 1, "type": 1, "season": 1, "date": "2022-04-20",
+        "time": "18:00:00", "duration": 90, "place": 5, "visibility": 0,
+        "description": null
+    }
+]"#;
+
+#[tokio::test]
+async fn class_events_are_sorted() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    transport.respond("/api/class/i/24772/events", 200, EVENTS);
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    let instance = client.get_class_instance(24772, &conf).await.unwrap();
+    let events = instance.events().await.unwrap();
+    assert_eq!(events.len(), 2);
+
+    let test = &events[0];
+    assert_eq!(test.event_type, ClassEventType::Test);
+    assert_eq!(test.season, Some(Season::Normal));
+    assert_eq!(test.visibility, FileVisibility::Public);
+    let date = NaiveDate::from_ymd_opt(2022, 4, 20).unwrap();
+    assert_eq!(
+        test.end(),
+        Some(date.and_time(NaiveTime::from_hms_opt(19, 30, 0).unwrap()))
+    );
+
+    let delivery = &events[1];
+    assert_eq!(delivery.event_type, ClassEventType::ProjectDelivery);
+    assert_eq!(delivery.start(), None);
+    assert!(delivery.get_place().await.unwrap().is_none());
+
+    instance.events().await.unwrap();
+    assert_eq!(transport.request_count(), 3);
+}
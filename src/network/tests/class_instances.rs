@@ -0,0 +1,34 @@
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+const CLASS_INSTANCE: &str = r#"
+{
+    "id": 24772, "parent": 449, "period": 3, "year": 2021,
+    "information": {"upstream": null}, "department": null,
+    "avg_grade": null, "enrollments": [], "shifts": [],
+    "url": "/faculdade/cadeira/i/24772/"
+}"#;
+
+#[tokio::test]
+async fn class_instances_are_cached() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    client.get_class_instance(24772, &conf).await.unwrap();
+    client.get_class_instance(24772, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+
+    // Unless the cache is evaded
+    let conf = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    client.get_class_instance(24772, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 3);
+}
@@ -1,9 +1,11 @@
+use crate::models;
 use crate::network::models::{
     Building, Class, ClassInfo, ClassInfoEntry, ClassInfoSources,
     ClassInstance, ClassInstanceFile, ClassInstanceFiles, ClassShift,
-    ClassShiftInstance, Department, File, FileCategory, Period, ShiftType,
-    Weekday,
+    ClassShiftInstance, Department, File, FileCategory, Period, Question,
+    QuestionAnswer, ShiftType, Weekday,
 };
+use chrono::{TimeZone, Utc};
 
 #[test]
 fn ok_buildings() {
@@ -436,6 +438,34 @@ fn ok_class_instance_shifts() {
     )
 }
 
+#[test]
+fn ok_class_shift_weekdays() {
+    let json = r#"
+{
+    "id": 42211,
+    "number": 1,
+    "type": 4,
+    "teachers": [],
+    "instances": [
+        {"weekday": 1, "start": 540, "duration": 120, "room": null},
+        {"weekday": 3, "start": 540, "duration": 120, "room": null}
+    ]
+}"#;
+
+    let parsed: ClassShift = serde_json::from_str(json).unwrap();
+
+    let weekdays: Vec<Weekday> = parsed
+        .instances
+        .iter()
+        .map(|instance| instance.weekday)
+        .collect();
+    assert_eq!(weekdays, vec![Weekday::Tuesday, Weekday::Thursday]);
+    assert!(
+        models::Weekday::from(Weekday::Tuesday)
+            < models::Weekday::from(Weekday::Thursday)
+    );
+}
+
 #[test]
 fn ok_class_instance_files() {
     let json = r#"
@@ -489,3 +519,48 @@ fn ok_class_instance_files() {
         }
     )
 }
+
+#[test]
+fn ok_question() {
+    let json = r#"
+{
+    "id": 12,
+    "title": "Derivada de x^x",
+    "content": "Como se deriva x^x?",
+    "user": 3,
+    "timestamp": "2021-10-03T14:00:00Z",
+    "sections": [7],
+    "answers": [
+        {
+            "id": 40,
+            "content": "Escrevendo como e^(x ln x)",
+            "user": null,
+            "timestamp": "2021-10-03T15:30:00Z",
+            "accepted": true
+        }
+    ]
+}"#;
+
+    let parsed: Question = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        parsed,
+        Question {
+            id: 12,
+            title: "Derivada de x^x".to_string(),
+            content: "Como se deriva x^x?".to_string(),
+            user: Some(3),
+            timestamp: Utc.with_ymd_and_hms(2021, 10, 3, 14, 0, 0).unwrap(),
+            sections: vec![7],
+            answers: vec![QuestionAnswer {
+                id: 40,
+                content: "Escrevendo como e^(x ln x)".to_string(),
+                user: None,
+                timestamp: Utc
+                    .with_ymd_and_hms(2021, 10, 3, 15, 30, 0)
+                    .unwrap(),
+                accepted: true
+            }]
+        }
+    )
+}
@@ -0,0 +1,93 @@
+use crate::errors::Error;
+use crate::network::tests::fake::FakeTransport;
+use crate::{
+    Endpoint, RequestConfig, RetryPolicy, Supernova, SupernovaBuilder,
+};
+
+use std::error::Error as StdError;
+use std::io;
+use std::sync::Arc;
+
+fn client(transport: &Arc<FakeTransport>) -> Arc<Supernova> {
+    SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::disabled())
+        .build()
+}
+
+#[tokio::test]
+async fn errors_carry_the_response() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/building/1", 400, r#"{"detail": "Bad id"}"#);
+    let client = client(&transport);
+
+    let err = client
+        .get_building(1, &RequestConfig::default())
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::Client(_)));
+    let http = err.http().unwrap();
+    assert_eq!(http.status, 400);
+    assert_eq!(http.endpoint, Some(Endpoint::Building(1)));
+    assert!(http.url.ends_with("/api/building/1"));
+    assert_eq!(http.detail.as_deref(), Some("Bad id"));
+    assert!(err.to_string().contains("Bad id"));
+}
+
+#[tokio::test]
+async fn body_is_the_message_lacking_a_detail() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/building/1", 500, "Internal Server Error\n");
+    let client = client(&transport);
+
+    let err = client
+        .get_building(1, &RequestConfig::default())
+        .await
+        .unwrap_err();
+    let http = err.http().unwrap();
+    assert_eq!(http.detail, None);
+    assert_eq!(http.message().as_deref(), Some("Internal Server Error"));
+}
+
+#[tokio::test]
+async fn redirects_are_not_missing_resources() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/building/1", 301, "");
+    let client = client(&transport);
+
+    assert!(matches!(
+        client.get_building(1, &RequestConfig::default()).await,
+        Err(Error::Redirection(_))
+    ));
+}
+
+#[tokio::test]
+async fn errors_are_classified() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/building/1", 503, "");
+    transport.respond("/api/building/2", 429, "");
+    transport.respond("/api/building/3", 403, "");
+    transport.respond("/api/building/4", 400, "");
+    let client = client(&transport);
+    let conf = RequestConfig::default();
+
+    let unavailable = client.get_building(1, &conf).await.unwrap_err();
+    assert!(unavailable.is_retryable() && !unavailable.is_auth());
+    let throttled = client.get_building(2, &conf).await.unwrap_err();
+    assert!(throttled.is_retryable() && !throttled.is_auth());
+    let forbidden = client.get_building(3, &conf).await.unwrap_err();
+    assert!(!forbidden.is_retryable() && forbidden.is_auth());
+    let bad = client.get_building(4, &conf).await.unwrap_err();
+    assert!(!bad.is_retryable() && !bad.is_auth());
+    assert!(Error::Timeout.is_retryable());
+    assert!(Error::MissingAuthentication.is_auth());
+}
+
+#[test]
+fn network_errors_keep_their_cause() {
+    let cause = io::Error::new(io::ErrorKind::ConnectionReset, "reset");
+    let err = Error::Network(Some(Box::new(cause)));
+
+    let source = err.source().unwrap();
+    assert_eq!(source.to_string(), "reset");
+}
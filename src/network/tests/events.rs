@@ -0,0 +1,103 @@
+use crate::models::GroupActivity;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+
+const EVENT: &str = r#"
+{
+    "id": 5, "title": "Hackathon", "description": "48 horas de código",
+    "start_date": "2022-05-20", "duration": 2880, "place": null,
+    "capacity": 60, "cost": null, "type": 5, "group": 3,
+    "schedule": [
+        {"start": "2022-05-20T09:00:00Z", "end": "2022-05-20T20:00:00Z", "place": 9},
+        {"start": "2022-05-21T09:00:00Z", "end": "2022-05-21T18:00:00Z", "place": null}
+    ],
+    "enrollment": {
+        "required": true, "open": true, "deadline": "2022-05-15T23:59:00Z",
+        "enrolled": 42, "url": null
+    }
+}"#;
+
+#[tokio::test]
+async fn events_are_detailed() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/event/5", 200, EVENT);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    let event = client.get_event(5, &conf).await.unwrap();
+    assert_eq!(event.schedule.len(), 2);
+    assert_eq!(
+        event.schedule[1].end,
+        Utc.with_ymd_and_hms(2022, 5, 21, 18, 0, 0).unwrap()
+    );
+    let enrollment = event.enrollment.as_ref().unwrap();
+    assert!(enrollment.required && enrollment.open);
+    assert_eq!(event.spots_left(), Some(18));
+
+    client.get_event(5, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn paged_events_are_fetched_for_details() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond(
+        "/api/events?limit=100&offset=0",
+        200,
+        r#"{"count": 1, "next": null, "previous": null, "results": [{
+            "id": 5, "title": "Hackathon", "description": "48 horas de código",
+            "start_date": "2022-05-20", "duration": 2880, "place": null,
+            "capacity": 60, "cost": null, "type": 5, "group": 3,
+            "enrollment": null
+        }]}"#,
+    );
+    transport.respond("/api/event/5", 200, EVENT);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    let page = client.get_events_front_page(&conf).await.unwrap().unwrap();
+    assert_eq!(page.items()[0].id, 5);
+    // Pages lack the schedule and enrollment
+    let event = client.get_event(5, &conf).await.unwrap();
+    assert_eq!(event.schedule.len(), 2);
+    assert!(event.enrollment.is_some());
+    client.get_event(5, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+}
+
+#[tokio::test]
+async fn announced_events_are_coerced() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/event/5", 200, EVENT);
+    transport.respond(
+        "/api/group/3",
+        200,
+        r#"{
+            "id": 3, "name": "Núcleo de Informática", "abbreviation": "NI",
+            "type": 1, "url": "/g/ni", "thumb": null, "outsiders_openness": 3,
+            "official": true,
+            "activities": [
+                {
+                    "resourcetype": "EventAnnouncement", "author": 7,
+                    "datetime": "2022-05-02T10:00:00Z", "event": 5
+                }
+            ],
+            "schedule_entries": [],
+            "events": []
+        }"#,
+    );
+    let client = Supernova::with_transport(transport);
+
+    let group = client.get_group(3).await.unwrap();
+    let activities = group.activities().await.unwrap();
+    let GroupActivity::EventAnnouncement(announcement) = &activities[0] else {
+        panic!("Expected an event announcement");
+    };
+    let event = announcement.event().await.unwrap();
+    assert_eq!(event.title, "Hackathon");
+    assert_eq!(event.group().await.unwrap().unwrap().id, 3);
+}
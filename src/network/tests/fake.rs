@@ -0,0 +1,94 @@
+use crate::errors::Error;
+use crate::network::transport::{HttpRequest, HttpResponse, Transport};
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+/// A fresh directory for the test called `name`
+pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+    let dir =
+        env::temp_dir().join(format!("iron_planet_{}_{}", process::id(), name));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+/// A transport that answers with canned responses and records every request
+#[derive(Default)]
+pub(crate) struct FakeTransport {
+    responses: Mutex<HashMap<String, Vec<(u16, String)>>>,
+    pub(crate) requests: Mutex<Vec<HttpRequest>>,
+    stall: Mutex<Option<Duration>>,
+    /// Requests being answered at the moment, and the most there ever were
+    in_flight: Mutex<(usize, usize)>,
+}
+
+impl FakeTransport {
+    /// Queues a response for the (upstream relative) `path`.
+    /// Queued responses are handed out in order, the last one being repeated.
+    pub(crate) fn respond(&self, path: &str, status: u16, body: &str) {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_default()
+            .push((status, body.to_string()));
+    }
+
+    /// Delays every response by `delay`, as a slow server would
+    pub(crate) fn stall(&self, delay: Duration) {
+        *self.stall.lock().unwrap() = Some(delay);
+    }
+
+    pub(crate) fn request_count(&self) -> usize {
+        self.requests.lock().unwrap().len()
+    }
+
+    /// The most requests that were ever being answered at once
+    pub(crate) fn max_in_flight(&self) -> usize {
+        self.in_flight.lock().unwrap().1
+    }
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        let path = request
+            .url
+            .split_once("://")
+            .and_then(|(_, rest)| rest.find('/').map(|pos| &rest[pos..]))
+            .unwrap_or(&request.url)
+            .to_string();
+        self.requests.lock().unwrap().push(request);
+
+        {
+            let mut in_flight = self.in_flight.lock().unwrap();
+            in_flight.0 += 1;
+            in_flight.1 = in_flight.1.max(in_flight.0);
+        }
+        let stall = *self.stall.lock().unwrap();
+        if let Some(delay) = stall {
+            tokio::time::sleep(delay).await;
+        }
+        self.in_flight.lock().unwrap().0 -= 1;
+
+        let mut responses = self.responses.lock().unwrap();
+        let queue = responses.get_mut(&path).ok_or(Error::Network(None))?;
+        let (status, body) = if queue.len() > 1 {
+            queue.remove(0)
+        } else {
+            queue[0].clone()
+        };
+        Ok(HttpResponse {
+            status,
+            headers: vec![],
+            body: body.into_bytes(),
+        })
+    }
+}
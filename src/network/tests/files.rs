@@ -0,0 +1,113 @@
+use crate::errors::Error;
+use crate::models::FileCategory;
+use crate::network::tests::fake::{scratch_dir, FakeTransport};
+use crate::{RequestConfig, Supernova};
+
+use std::fs;
+use std::sync::Arc;
+
+const CONTENT: &str = "Regulamento de estagios";
+const HASH: &str = "ab3aca4db4825c14bb0fdbf73d5514a2dccf7ec5";
+
+fn files_json(hash: &str) -> String {
+    format!(
+        r#"{{
+    "official": [
+        {{
+            "id": 214498,
+            "file": {{
+                "hash": "{hash}", "size": 23, "mime": "application/pdf",
+                "license": "Todos os direitos reservados",
+                "url": "/faculdade/ficheiro/{hash}/"
+            }},
+            "name": "regulamento_estagios.pdf",
+            "category": 9,
+            "upload_datetime": "2021-03-24T02:06:49.551799Z",
+            "uploader": null,
+            "uploader_teacher": 879,
+            "url": "/faculdade/cadeira/i/24772/ficheiro/214498"
+        }}
+    ],
+    "community": []
+}}"#
+    )
+}
+
+fn authenticated_client(transport: &Arc<FakeTransport>) -> Arc<Supernova> {
+    transport.respond("/api/validation", 200, r#""Success""#);
+    Supernova::with_transport(transport.clone())
+}
+
+#[tokio::test]
+async fn files_are_listed_and_downloaded() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/class/i/24772/files", 200, &files_json(HASH));
+    transport.respond(&format!("/faculdade/ficheiro/{HASH}/"), 200, CONTENT);
+    let client = authenticated_client(&transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    let files = client.get_class_instance_files(24772, &conf).await.unwrap();
+    client.get_class_instance_files(24772, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+    let file = &files.official[0];
+    assert_eq!(file.category, FileCategory::Others);
+
+    let dir = scratch_dir("download");
+    let destination = dir.join("2021").join(&file.name);
+    let mut progress = vec![];
+    client
+        .download_file(
+            &file.file,
+            &destination,
+            |update| progress.push(update),
+            &conf,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(fs::read_to_string(&destination).unwrap(), CONTENT);
+    let last = progress.last().unwrap();
+    assert_eq!((last.received, last.total), (23, 23));
+    // Files aren't necessarily public
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(
+        requests.last().unwrap().header("Authorization"),
+        Some("Token abc")
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn corrupt_downloads_are_discarded() {
+    let transport = Arc::new(FakeTransport::default());
+    let wrong_hash = "0".repeat(40);
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        &files_json(&wrong_hash),
+    );
+    transport.respond(
+        &format!("/faculdade/ficheiro/{wrong_hash}/"),
+        200,
+        CONTENT,
+    );
+    let client = authenticated_client(&transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    let files = client.get_class_instance_files(24772, &conf).await.unwrap();
+    let dir = scratch_dir("corrupt_download");
+    let destination = dir.join("regulamento_estagios.pdf");
+    let err = client
+        .download_file(&files.official[0].file, &destination, |_| {}, &conf)
+        .await
+        .unwrap_err();
+
+    assert!(
+        matches!(err, Error::HashMismatch(expected, actual) if expected == wrong_hash && actual == HASH)
+    );
+    assert!(!destination.exists());
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,133 @@
+use crate::hours::{OpeningHours, OpeningPeriod, Transition};
+use crate::models::Weekday;
+
+use chrono::{FixedOffset, NaiveDate, NaiveTime, TimeZone, Utc};
+
+fn time(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+fn period(weekday: Weekday, start: NaiveTime, end: NaiveTime) -> OpeningPeriod {
+    OpeningPeriod {
+        weekday,
+        start,
+        end,
+    }
+}
+
+fn canteen() -> OpeningHours {
+    OpeningHours::weekly(
+        [
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+        ]
+        .into_iter()
+        .map(|weekday| period(weekday, time(12, 0), time(14, 30)))
+        .collect(),
+    )
+}
+
+#[test]
+fn weekly_periods() {
+    let hours = canteen();
+    // 2022-05-02 was a Monday
+    let monday_lunch = Utc.with_ymd_and_hms(2022, 5, 2, 13, 0, 0).unwrap();
+    assert!(hours.is_open_at(&monday_lunch));
+    let monday_close = Utc.with_ymd_and_hms(2022, 5, 2, 14, 30, 0).unwrap();
+    assert!(!hours.is_open_at(&monday_close));
+    let saturday_lunch = Utc.with_ymd_and_hms(2022, 5, 7, 13, 0, 0).unwrap();
+    assert!(!hours.is_open_at(&saturday_lunch));
+
+    assert_eq!(
+        hours.next_transition(&monday_lunch),
+        Some(Transition {
+            at: monday_close,
+            opens: false
+        })
+    );
+    // Over the weekend
+    assert_eq!(
+        hours.next_transition(&saturday_lunch),
+        Some(Transition {
+            at: Utc.with_ymd_and_hms(2022, 5, 9, 12, 0, 0).unwrap(),
+            opens: true
+        })
+    );
+}
+
+#[test]
+fn periods_past_midnight() {
+    let hours = OpeningHours::weekly(vec![period(
+        Weekday::Friday,
+        time(20, 0),
+        time(2, 0),
+    )]);
+    let saturday_night = Utc.with_ymd_and_hms(2022, 5, 7, 1, 0, 0).unwrap();
+    assert!(hours.is_open_at(&saturday_night));
+    assert_eq!(
+        hours.next_transition(&saturday_night).unwrap().at,
+        Utc.with_ymd_and_hms(2022, 5, 7, 2, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn exceptions_and_holidays() {
+    let may_2 = NaiveDate::from_ymd_opt(2022, 5, 2).unwrap();
+    let may_3 = NaiveDate::from_ymd_opt(2022, 5, 3).unwrap();
+    let hours = canteen()
+        .with_holidays([may_2, may_3])
+        .with_exception(may_3, vec![(time(12, 0), time(13, 0))]);
+
+    let monday_lunch = Utc.with_ymd_and_hms(2022, 5, 2, 13, 0, 0).unwrap();
+    assert!(!hours.is_open_at(&monday_lunch));
+    // The exception takes precedence over the holiday
+    assert_eq!(
+        hours.next_transition(&monday_lunch),
+        Some(Transition {
+            at: Utc.with_ymd_and_hms(2022, 5, 3, 12, 0, 0).unwrap(),
+            opens: true
+        })
+    );
+    let tuesday_lunch = Utc.with_ymd_and_hms(2022, 5, 3, 12, 30, 0).unwrap();
+    assert!(hours.is_open_at(&tuesday_lunch));
+    assert_eq!(
+        hours.next_transition(&tuesday_lunch).unwrap().at,
+        Utc.with_ymd_and_hms(2022, 5, 3, 13, 0, 0).unwrap()
+    );
+}
+
+#[test]
+fn open_around_the_clock() {
+    let hours = OpeningHours::weekly(
+        [
+            Weekday::Monday,
+            Weekday::Tuesday,
+            Weekday::Wednesday,
+            Weekday::Thursday,
+            Weekday::Friday,
+            Weekday::Saturday,
+            Weekday::Sunday,
+        ]
+        .into_iter()
+        .map(|weekday| period(weekday, time(0, 0), time(0, 0)))
+        .collect(),
+    );
+    let now = Utc.with_ymd_and_hms(2022, 5, 2, 13, 0, 0).unwrap();
+    assert!(hours.is_open_at(&now));
+    assert_eq!(hours.next_transition(&now), None);
+    assert_eq!(OpeningHours::default().next_transition(&now), None);
+}
+
+#[test]
+fn local_time_is_used() {
+    let lisbon_summer = FixedOffset::east_opt(3600).unwrap();
+    let at = lisbon_summer
+        .with_ymd_and_hms(2022, 5, 2, 12, 15, 0)
+        .unwrap();
+    assert!(canteen().is_open_at(&at));
+    // The very same instant, which is still before noon in UTC
+    assert!(!canteen().is_open_at(&at.with_timezone(&Utc)));
+}
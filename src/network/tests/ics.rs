@@ -0,0 +1,269 @@
+use crate::academic::{AcademicCalendar, Term};
+use crate::ics::IcsExporter;
+use crate::models::Period;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::NaiveDate;
+
+const STUDENT: &str = r#"
+{
+    "id": 12, "name": "Cláudio", "abbreviation": null, "number": 12,
+    "enrollments": [5], "shifts": [1], "first_year": 2020,
+    "last_year": null, "course": null, "avg_grade": null, "url": "/s/12"
+}"#;
+
+const ENROLLMENT: &str = r#"
+{
+    "id": 5, "class_instance": 24772, "student": 12, "attendance": null,
+    "attendance_date": null, "normal_grade": null,
+    "normal_grade_date": null, "recourse_grade": null,
+    "recourse_grade_date": null, "special_grade": null,
+    "special_grade_date": null, "improvement_grade": null,
+    "improvement_grade_date": null, "approved": null, "grade": null
+}"#;
+
+const CLASS: &str = r#"
+{
+    "id": 449, "name": "Álgebra Linear; Teoria", "abbreviation": "AL",
+    "credits": 6, "department": 4, "instances": [24772],
+    "url": "/faculdade/cadeira/449/"
+}"#;
+
+const TEACHER: &str = r#"
+{
+    "id": 3, "name": "Ana Sousa", "short_name": "Ana Sousa",
+    "abbreviation": "AS", "first_year": 2010, "last_year": null,
+    "phone": null, "email": null, "thumb": null, "rank": null,
+    "departments": [4], "shifts": [1], "url": "/t/3"
+}"#;
+
+const CLASS_INSTANCE: &str = r#"
+{
+    "id": 24772, "parent": 449, "period": 3, "year": 2021,
+    "information": {"upstream": null}, "department": null,
+    "avg_grade": null, "enrollments": [],
+    "shifts": [
+        {
+            "id": 1, "number": 2, "type": 3, "teachers": [],
+            "instances": [
+                {"weekday": 0, "start": 540, "duration": 120, "room": 10},
+                {"weekday": 2, "start": 840, "duration": 90, "room": null}
+            ]
+        }
+    ],
+    "url": "/faculdade/cadeira/i/24772/"
+}"#;
+
+const SHIFT: &str = r#"
+{
+    "id": 1, "number": 2, "type": 3, "teachers": [3],
+    "instances": [
+        {"weekday": 0, "start": 540, "duration": 120, "room": 10},
+        {"weekday": 2, "start": 840, "duration": 90, "room": null}
+    ]
+}"#;
+
+const PLACE: &str = r#"
+{
+    "id": 10, "name": "Sala 127", "floor": 1, "building": 2,
+    "picture": null, "cover": null, "features": [], "room_meta": null
+}"#;
+
+const BUILDING: &str = r#"
+{
+    "id": 2, "name": "Edifício II", "abbreviation": "II", "places": [10],
+    "url": "/faculdade/campus/edificio/2/"
+}"#;
+
+fn serve(transport: &FakeTransport) {
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/student/12", 200, STUDENT);
+    transport.respond("/api/enrollment/5", 200, ENROLLMENT);
+    transport.respond("/api/class/449", 200, CLASS);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    transport.respond("/api/place/10", 200, PLACE);
+    transport.respond("/api/building/2", 200, BUILDING);
+}
+
+fn second_semester() -> AcademicCalendar {
+    AcademicCalendar::new()
+        .with_term(Term::new(
+            2021,
+            Period::SecondSemester,
+            NaiveDate::from_ymd_opt(2022, 2, 14).unwrap(),
+            NaiveDate::from_ymd_opt(2022, 5, 27).unwrap(),
+        ))
+        .with_holidays([NaiveDate::from_ymd_opt(2022, 4, 25).unwrap()])
+}
+
+/// The events as lists of properties, sans timestamps
+fn events(ics: &str) -> Vec<Vec<&str>> {
+    ics.split("BEGIN:VEVENT\r\n")
+        .skip(1)
+        .map(|event| {
+            event
+                .lines()
+                .take_while(|line| *line != "END:VEVENT")
+                .filter(|line| !line.starts_with("DTSTAMP:"))
+                .collect()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn student_shifts_become_weekly_events() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let student = client
+        .get_student(12, &RequestConfig::default())
+        .await
+        .unwrap();
+
+    let ics = IcsExporter::new(second_semester())
+        .name("Horário")
+        .student(&student)
+        .await
+        .unwrap();
+
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ics.contains("\r\nX-WR-CALNAME:Horário\r\n"));
+    assert!(ics.contains("\r\nBEGIN:VTIMEZONE\r\nTZID:Europe/Lisbon\r\n"));
+    assert!(ics.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    assert_eq!(
+        events(&ics),
+        vec![
+            vec![
+                "UID:shift-1-0@iron-planet",
+                "DTSTART;TZID=Europe/Lisbon:20220214T090000",
+                "DTEND;TZID=Europe/Lisbon:20220214T110000",
+                "RRULE:FREQ=WEEKLY;UNTIL=20220523T080000Z",
+                "EXDATE;TZID=Europe/Lisbon:20220425T090000",
+                "SUMMARY:AL TP2",
+                r"DESCRIPTION:Álgebra Linear\; Teoria",
+                r"LOCATION:Sala 127\, Edifício II",
+            ],
+            vec![
+                "UID:shift-1-1@iron-planet",
+                "DTSTART;TZID=Europe/Lisbon:20220216T140000",
+                "DTEND;TZID=Europe/Lisbon:20220216T153000",
+                "RRULE:FREQ=WEEKLY;UNTIL=20220525T130000Z",
+                "SUMMARY:AL TP2",
+                r"DESCRIPTION:Álgebra Linear\; Teoria",
+            ],
+        ]
+    );
+    // The shift was known from its class instance
+    assert!(!transport
+        .requests
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|request| request.url.ends_with("/api/shift/1")));
+}
+
+#[tokio::test]
+async fn teacher_shifts_are_found_within_their_departments() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    // The other shift is of a term long gone
+    transport.respond(
+        "/api/teacher/3",
+        200,
+        &TEACHER.replace(r#""shifts": [1]"#, r#""shifts": [1, 8]"#),
+    );
+    transport.respond("/api/shift/1", 200, SHIFT);
+    transport.respond(
+        "/api/shift/8",
+        200,
+        &SHIFT.replace(r#""id": 1,"#, r#""id": 8,"#),
+    );
+    // Instances older than the calendar's terms are not looked into
+    let class = CLASS.replace("[24772]", "[24772, 20000, 17000]");
+    transport.respond("/api/classes", 200, &format!("[{class}]"));
+    transport.respond(
+        "/api/class/i/20000",
+        200,
+        &CLASS_INSTANCE
+            .replace("24772", "20000")
+            .replace("2021", "2019")
+            .replace(r#""id": 1,"#, r#""id": 7,"#),
+    );
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+    let teacher = client.get_teacher(3, &conf).await.unwrap();
+
+    let ics = IcsExporter::new(second_semester())
+        .teacher(&teacher, &conf)
+        .await
+        .unwrap();
+    let events = events(&ics);
+    assert_eq!(events.len(), 2);
+    assert!(events[0].contains(&"SUMMARY:AL TP2"));
+    assert!(events[1].contains(&"DTSTART;TZID=Europe/Lisbon:20220216T140000"));
+    assert!(!transport
+        .requests
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|request| request.url.ends_with("/api/class/i/17000")));
+}
+
+#[tokio::test]
+async fn shifts_out_of_known_terms_are_left_out() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let student = client
+        .get_student(12, &RequestConfig::default())
+        .await
+        .unwrap();
+
+    let calendar = AcademicCalendar::new().with_term(Term::new(
+        2021,
+        Period::FirstSemester,
+        NaiveDate::from_ymd_opt(2021, 9, 13).unwrap(),
+        NaiveDate::from_ymd_opt(2021, 12, 17).unwrap(),
+    ));
+    let ics = IcsExporter::new(calendar).student(&student).await.unwrap();
+    assert!(events(&ics).is_empty());
+}
+
+#[tokio::test]
+async fn shifts_learn_the_class_instance_they_were_seen_within() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    transport.respond("/api/shift/1", 200, SHIFT);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    let shift = client.get_shift(1, &conf).await.unwrap();
+    assert!(shift.get_class_instance().await.unwrap().is_none());
+
+    client.get_class_instance(24772, &conf).await.unwrap();
+    let shift = client.get_shift(1, &conf).await.unwrap();
+    let class_inst = shift.get_class_instance().await.unwrap().unwrap();
+    assert_eq!(class_inst.id, 24772);
+}
+
+#[tokio::test]
+async fn long_lines_are_folded() {
+    let name = "Horário ".repeat(20);
+    let ics = IcsExporter::new(AcademicCalendar::new())
+        .name(&name)
+        .shifts(&[], &[])
+        .await
+        .unwrap();
+    for line in ics.split("\r\n") {
+        assert!(line.len() <= 75);
+    }
+    let unfolded = ics.replace("\r\n ", "");
+    assert!(unfolded.contains(&format!("X-WR-CALNAME:{name}\r\n")));
+}
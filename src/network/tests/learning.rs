@@ -0,0 +1,83 @@
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+fn learning_tree() -> Arc<FakeTransport> {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond(
+        "/api/learning/areas",
+        200,
+        r#"[{"id": 1, "title": "Matemática", "description": null,
+            "subareas": [2]}]"#,
+    );
+    transport.respond(
+        "/api/learning/subarea/2",
+        200,
+        r#"{"id": 2, "title": "Cálculo", "description": "Análise real",
+            "area": 1, "sections": [3]}"#,
+    );
+    transport.respond(
+        "/api/learning/section/3",
+        200,
+        r#"{"id": 3, "title": "Derivadas", "description": null,
+            "content": null, "subarea": 2, "parents": [], "children": [],
+            "questions": [4]}"#,
+    );
+    transport.respond(
+        "/api/learning/question/4",
+        200,
+        r#"{"id": 4, "title": "Derivada de x^x",
+            "content": "Como se deriva?", "user": null,
+            "timestamp": "2021-10-03T14:00:00Z", "sections": [3],
+            "answers": [
+                {"id": 5, "content": "Logaritmos", "user": null,
+                 "timestamp": "2021-10-03T15:00:00Z", "accepted": false},
+                {"id": 6, "content": "e^(x ln x)", "user": null,
+                 "timestamp": "2021-10-03T16:00:00Z", "accepted": true}
+            ]}"#,
+    );
+    transport
+}
+
+#[tokio::test]
+async fn learning_tree_is_browsable() {
+    let transport = learning_tree();
+    let client = Supernova::with_transport(transport.clone());
+
+    let areas = client
+        .get_learning_areas(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(areas.len(), 1);
+    let subareas = areas[0].get_subareas().await.unwrap();
+    assert_eq!(subareas[0].title, "Cálculo");
+    assert_eq!(subareas[0].get_area().await.unwrap(), areas[0]);
+    let sections = subareas[0].get_sections().await.unwrap();
+    assert_eq!(sections[0].title, "Derivadas");
+    let questions = sections[0].get_questions().await.unwrap();
+    assert_eq!(questions[0].answers.len(), 2);
+    assert_eq!(questions[0].accepted_answer().unwrap().id, 6);
+    assert!(questions[0].matches("DERIVADA deriva"));
+    assert!(!questions[0].matches("integral"));
+}
+
+#[tokio::test]
+async fn learning_entities_are_cached() {
+    let transport = learning_tree();
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    client.get_learning_areas(&conf).await.unwrap();
+    client.get_learning_area(1, &conf).await.unwrap();
+    client.get_question(4, &conf).await.unwrap();
+    client.get_question(4, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+
+    let evasive = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    client.get_question(4, &evasive).await.unwrap();
+    assert_eq!(transport.request_count(), 3);
+}
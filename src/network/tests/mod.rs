@@ -1,2 +1,48 @@
 #[cfg(test)]
+mod academic;
+#[cfg(test)]
+mod auth;
+#[cfg(test)]
+mod calendar;
+#[cfg(test)]
+mod class_events;
+#[cfg(test)]
+mod class_instances;
+#[cfg(test)]
 mod deserialization;
+#[cfg(test)]
+mod errors;
+#[cfg(test)]
+mod events;
+#[cfg(test)]
+mod fake;
+#[cfg(test)]
+mod files;
+#[cfg(test)]
+mod hours;
+#[cfg(test)]
+mod ics;
+#[cfg(test)]
+mod learning;
+#[cfg(test)]
+mod retry;
+#[cfg(test)]
+mod rooms;
+#[cfg(test)]
+mod services;
+#[cfg(test)]
+mod session;
+#[cfg(test)]
+mod sync;
+#[cfg(test)]
+mod timeout;
+#[cfg(test)]
+mod timetable;
+#[cfg(test)]
+mod transport;
+#[cfg(test)]
+mod transportation;
+#[cfg(test)]
+mod users;
+#[cfg(test)]
+mod weather;
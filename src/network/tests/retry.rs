@@ -0,0 +1,104 @@
+use crate::errors::Error;
+use crate::network::retry::{parse_retry_after, RetryPolicy};
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova, SupernovaBuilder};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+fn instant_policy() -> RetryPolicy {
+    RetryPolicy {
+        initial_backoff: Duration::ZERO,
+        jitter: false,
+        ..RetryPolicy::default()
+    }
+}
+
+fn client(transport: &Arc<FakeTransport>) -> Arc<Supernova> {
+    SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(instant_policy())
+        .build()
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 502, "");
+    transport.respond("/api/buildings", 503, "");
+    transport.respond("/api/buildings", 200, "[]");
+    let client = client(&transport);
+
+    let buildings = client
+        .get_buildings(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert!(buildings.is_empty());
+    assert_eq!(transport.request_count(), 3);
+}
+
+#[tokio::test]
+async fn retries_give_up_after_max_attempts() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 502, "");
+    let client = client(&transport);
+
+    assert!(matches!(
+        client.get_buildings(&RequestConfig::default()).await,
+        Err(Error::Server(_))
+    ));
+    assert_eq!(transport.request_count(), 3);
+}
+
+#[tokio::test]
+async fn non_idempotent_requests_are_not_retried() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/login", 503, "");
+    let client = client(&transport);
+
+    assert!(client.login("user", "pass").await.is_err());
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[test]
+fn backoff_grows_exponentially_up_to_a_cap() {
+    let policy = RetryPolicy {
+        max_attempts: 10,
+        initial_backoff: Duration::from_secs(1),
+        max_backoff: Duration::from_secs(5),
+        jitter: false,
+        ..RetryPolicy::default()
+    };
+    assert_eq!(policy.delay(1, None), Some(Duration::from_secs(1)));
+    assert_eq!(policy.delay(2, None), Some(Duration::from_secs(2)));
+    assert_eq!(policy.delay(3, None), Some(Duration::from_secs(4)));
+    assert_eq!(policy.delay(4, None), Some(Duration::from_secs(5)));
+    assert_eq!(policy.delay(10, None), None);
+
+    // Retry-After takes precedence, unless it asks for too much
+    assert_eq!(policy.delay(1, Some("3")), Some(Duration::from_secs(3)));
+    assert_eq!(policy.delay(1, Some("60")), None);
+}
+
+#[test]
+fn jitter_stays_within_bounds() {
+    let policy = RetryPolicy {
+        initial_backoff: Duration::from_secs(2),
+        ..RetryPolicy::default()
+    };
+    for _ in 0..100 {
+        let delay = policy.delay(1, None).unwrap();
+        assert!(delay >= Duration::from_secs(1));
+        assert!(delay <= Duration::from_secs(2));
+    }
+}
+
+#[test]
+fn retry_after_parsing() {
+    assert_eq!(parse_retry_after("90"), Some(Duration::from_secs(90)));
+    assert_eq!(
+        parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+        Some(Duration::ZERO)
+    );
+    assert_eq!(parse_retry_after("soon"), None);
+}
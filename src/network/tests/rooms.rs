@@ -0,0 +1,275 @@
+use crate::models::{Period, RoomType, ShiftType, Weekday};
+use crate::network::tests::fake::FakeTransport;
+use crate::rooms::RoomFilter;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::NaiveTime;
+
+const CLASSES: &str = r#"
+[
+    {
+        "id": 449, "name": "Álgebra Linear", "abbreviation": "AL",
+        "credits": 6, "department": null, "instances": [1, 9],
+        "url": "/faculdade/cadeira/449/"
+    }
+]"#;
+
+const TEACHER: &str = r#"
+{
+    "id": 879, "name": "Maria Fernandes", "short_name": "Maria Fernandes",
+    "abbreviation": "MF", "first_year": 2015, "last_year": null,
+    "phone": null, "email": null, "thumb": null, "rank": null,
+    "departments": [], "shifts": [1, 2], "url": "/faculdade/professor/879/"
+}"#;
+
+fn class_instance(id: u32, year: u32, shifts: &str) -> String {
+    format!(
+        r#"{{
+            "id": {id}, "parent": 449, "period": 3, "year": {year},
+            "information": {{"upstream": null}}, "department": null,
+            "avg_grade": null, "enrollments": [], "shifts": [{shifts}],
+            "url": "/faculdade/cadeira/i/{id}/"
+        }}"#
+    )
+}
+
+fn shift(id: u32, start: u16, room: u32) -> String {
+    format!(
+        r#"{{
+            "id": {id}, "number": 1, "type": 2, "teachers": [879],
+            "instances": [{{
+                "weekday": 1, "start": {start}, "duration": 120,
+                "room": {room}
+            }}]
+        }}"#
+    )
+}
+
+fn room(
+    id: u32,
+    building: u32,
+    room_type: u8,
+    capacity: u16,
+    extinguished: bool,
+) -> String {
+    format!(
+        r#"{{
+            "id": {id}, "name": "Sala {id}", "floor": 1,
+            "building": {building}, "picture": null, "cover": null,
+            "features": [],
+            "room_meta": {{
+                "title": "Sala {id}", "department": null,
+                "capacity": {capacity}, "door_number": {id},
+                "type": {room_type}, "extinguished": {extinguished},
+                "description": null, "equipment": null,
+                "url": "/faculdade/campus/sala/{id}/"
+            }}
+        }}"#
+    )
+}
+
+fn serve(transport: &FakeTransport) {
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/classes", 200, CLASSES);
+    transport.respond(
+        "/api/class/449",
+        200,
+        CLASSES.trim().trim_start_matches('[').trim_end_matches(']'),
+    );
+    transport.respond("/api/teacher/879", 200, TEACHER);
+    let shifts = [shift(1, 840, 10), shift(2, 540, 11)].join(",");
+    transport.respond("/api/class/i/1", 200, &class_instance(1, 2021, &shifts));
+    // Same semester, another year
+    transport.respond(
+        "/api/class/i/9",
+        200,
+        &class_instance(9, 2020, &shift(3, 840, 11)),
+    );
+    let places = [
+        room(10, 2, 4, 30, false),
+        room(11, 2, 4, 40, false),
+        room(12, 2, 4, 20, false),
+        room(13, 3, 4, 50, false),
+        room(14, 2, 2, 60, false),
+        room(15, 2, 4, 35, true),
+        r#"{
+            "id": 16, "name": "Bar", "floor": 0, "building": 2,
+            "picture": null, "cover": null, "features": [],
+            "room_meta": null
+        }"#
+        .to_string(),
+    ]
+    .join(",");
+    transport.respond("/api/places", 200, &format!("[{places}]"));
+}
+
+fn time(hour: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+}
+
+#[tokio::test]
+async fn occupancy_is_that_of_the_period() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+
+    let occupancy = client
+        .room_occupancy(2021, Period::SecondSemester, &conf)
+        .await
+        .unwrap();
+    assert_eq!(occupancy.of(10).len(), 1);
+    assert_eq!(occupancy.of(10)[0].shift.id, 1);
+    assert_eq!(occupancy.of(10)[0].end, time(16));
+    assert_eq!(occupancy.of(11).len(), 1);
+    assert!(occupancy.of(12).is_empty());
+
+    assert!(!occupancy.is_free(10, Weekday::Tuesday, time(15), time(17)));
+    assert!(occupancy.is_free(10, Weekday::Tuesday, time(16), time(18)));
+    assert!(occupancy.is_free(10, Weekday::Wednesday, time(14), time(16)));
+
+    // Class instances are only fetched once
+    let requests = transport.request_count();
+    client
+        .room_occupancy(2021, Period::SecondSemester, &conf)
+        .await
+        .unwrap();
+    assert_eq!(transport.request_count(), requests);
+}
+
+#[tokio::test]
+async fn occupancy_fetches_are_bounded() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    let ids = (1..=6).map(|id| id.to_string()).collect::<Vec<_>>();
+    transport.respond(
+        "/api/classes",
+        200,
+        &CLASSES.replace("[1, 9]", &format!("[{}]", ids.join(", "))),
+    );
+    for id in 1..=6 {
+        transport.respond(
+            &format!("/api/class/i/{id}"),
+            200,
+            &class_instance(id, 2021, &shift(id, 840, 10)),
+        );
+    }
+    let client = Supernova::builder()
+        .transport(transport.clone())
+        .concurrent_fetches(2)
+        .build();
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+    // Instances fetched before are not fetched again
+    client.get_class_instance(1, &conf).await.unwrap();
+    let requests = transport.request_count();
+
+    transport.stall(Duration::from_millis(20));
+    let occupancy = client
+        .room_occupancy(2021, Period::SecondSemester, &conf)
+        .await
+        .unwrap();
+    assert_eq!(occupancy.of(10).len(), 6);
+    assert_eq!(transport.max_in_flight(), 2);
+    // The listing of classes and the 5 instances not known
+    assert_eq!(transport.request_count(), requests + 6);
+}
+
+#[tokio::test]
+async fn free_rooms_are_filtered() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+    let occupancy = client
+        .room_occupancy(2021, Period::SecondSemester, &conf)
+        .await
+        .unwrap();
+
+    let filter = RoomFilter::new()
+        .in_building(2)
+        .min_capacity(30)
+        .room_type(RoomType::Laboratory);
+    let ids = |rooms: Vec<crate::models::Place>| {
+        rooms.iter().map(|place| place.id).collect::<Vec<_>>()
+    };
+
+    let rooms = client
+        .free_rooms(
+            &occupancy,
+            Weekday::Tuesday,
+            time(14),
+            time(16),
+            &filter,
+            &conf,
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(rooms), vec![11]);
+
+    let rooms = client
+        .free_rooms(
+            &occupancy,
+            Weekday::Tuesday,
+            time(9),
+            time(10),
+            &filter,
+            &conf,
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(rooms), vec![10]);
+
+    // Anything but extinguished rooms and other places
+    let rooms = client
+        .free_rooms(
+            &occupancy,
+            Weekday::Monday,
+            time(9),
+            time(10),
+            &RoomFilter::new(),
+            &conf,
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids(rooms), vec![10, 11, 12, 13, 14]);
+}
+
+#[tokio::test]
+async fn rooms_have_weekly_schedules() {
+    let transport = Arc::new(FakeTransport::default());
+    serve(&transport);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+    let places = client.get_places(&conf).await.unwrap();
+    let place = places.iter().find(|place| place.id == 11).unwrap();
+
+    let occupancy = client
+        .room_occupancy(2021, Period::SecondSemester, &conf)
+        .await
+        .unwrap();
+    let schedule = place.schedule(&occupancy, &conf).await.unwrap();
+    // The lesson of the year before is not there
+    assert_eq!(schedule.days.len(), 1);
+    assert!(schedule.on(Weekday::Monday).is_empty());
+    let lessons = schedule.on(Weekday::Tuesday);
+    assert_eq!(lessons.len(), 1);
+    assert_eq!(lessons[0].class.abbreviation, "AL");
+    assert_eq!(lessons[0].shift.id, 2);
+    assert_eq!(lessons[0].shift.shift_type, ShiftType::Practical);
+    assert_eq!(lessons[0].teachers[0].name, "Maria Fernandes");
+    assert_eq!((lessons[0].start, lessons[0].end), (time(9), time(11)));
+
+    let occupancy = client
+        .room_occupancy(2020, Period::SecondSemester, &conf)
+        .await
+        .unwrap();
+    let schedule = place.schedule(&occupancy, &conf).await.unwrap();
+    assert_eq!(schedule.on(Weekday::Tuesday)[0].shift.id, 3);
+}
@@ -0,0 +1,156 @@
+use crate::models::{ServiceType, Weekday};
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+
+const SERVICES: &str = r#"
+[
+    {
+        "id": 1, "name": "Cantina", "type": "canteen", "description": null,
+        "place": 3,
+        "opening_hours": [
+            {"weekday": 0, "start": "12:00:00", "end": "14:30:00"}
+        ]
+    },
+    {
+        "id": 2, "name": "Loja", "type": "kiosk", "description": null,
+        "place": null, "opening_hours": []
+    }
+]"#;
+
+const SERVICE: &str = r#"
+{
+    "id": 1, "name": "Cantina", "type": "canteen", "description": null,
+    "place": 3,
+    "opening_hours": [
+        {"weekday": 0, "start": "12:00:00", "end": "14:30:00"}
+    ],
+    "menu": [
+        {
+            "date": "2022-05-02",
+            "items": [
+                {"name": "Sopa", "category": "soup", "price": 0.6},
+                {"name": "Bacalhau", "category": "fish", "price": 2.75}
+            ]
+        }
+    ]
+}"#;
+
+#[tokio::test]
+async fn services_are_listed_and_cached() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/services", 200, SERVICES);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    let mut services = client.get_services(&conf).await.unwrap();
+    services.sort_by_key(|service| service.id);
+    assert_eq!(services.len(), 2);
+    assert_eq!(services[0].service_type, ServiceType::Canteen);
+    assert_eq!(services[0].opening_hours.weekly.len(), 1);
+    assert_eq!(services[1].service_type, ServiceType::Other);
+    assert!(services[0].menu.is_none());
+
+    client.get_services(&conf).await.unwrap();
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn service_details_include_the_menu() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/services", 200, SERVICES);
+    transport.respond("/api/services/1", 200, SERVICE);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    client.get_services(&conf).await.unwrap();
+    // The listing lacks the menu, so the details are still requested
+    let service = client.get_service(1, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+
+    let date = NaiveDate::from_ymd_opt(2022, 5, 2).unwrap();
+    let menu = service.menu_on(date).unwrap();
+    assert_eq!(menu.items.len(), 2);
+    assert_eq!(menu.items[1].price, Some(2.75));
+    assert!(service.menu_on(date.succ_opt().unwrap()).is_none());
+
+    client.get_service(1, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+}
+
+#[tokio::test]
+async fn listings_replace_known_services() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/services", 200, SERVICES);
+    // Later on, the kiosk is gone
+    transport.respond(
+        "/api/services",
+        200,
+        r#"[{"id": 1, "name": "Cantina", "type": "canteen",
+            "description": null, "place": 3, "opening_hours": []}]"#,
+    );
+    transport.respond("/api/services/1", 200, SERVICE);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    client.get_services(&conf).await.unwrap();
+    assert!(client.get_service(1, &conf).await.unwrap().menu.is_some());
+
+    let refresh = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    let services = client.get_services(&refresh).await.unwrap();
+    assert_eq!(services.len(), 1);
+    assert!(services[0].opening_hours.weekly.is_empty());
+    // The menu is not carried over from before, but fetched anew
+    assert!(services[0].menu.is_none());
+    let requests = transport.request_count();
+    client.get_service(1, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), requests + 1);
+}
+
+#[tokio::test]
+async fn services_without_a_menu_are_fetched_once() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond(
+        "/api/services/2",
+        200,
+        r#"{"id": 2, "name": "Loja", "type": "kiosk", "description": null,
+            "place": null, "opening_hours": [], "menu": null}"#,
+    );
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    let service = client.get_service(2, &conf).await.unwrap();
+    assert!(service.menu.is_none());
+    client.get_service(2, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn weekdays_are_numbered_from_monday() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond(
+        "/api/services/1",
+        200,
+        r#"{
+            "id": 1, "name": "Biblioteca", "type": "library",
+            "description": null, "place": null, "menu": null,
+            "opening_hours": [
+                {"weekday": 1, "start": "09:00:00", "end": "18:00:00"}
+            ]
+        }"#,
+    );
+    let client = Supernova::with_transport(transport);
+    let service = client
+        .get_service(1, &RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(service.opening_hours.weekly[0].weekday, Weekday::Tuesday);
+    let tuesday = Utc.with_ymd_and_hms(2022, 5, 3, 10, 0, 0).unwrap();
+    assert!(service.opening_hours.is_open_at(&tuesday));
+}
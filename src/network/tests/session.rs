@@ -0,0 +1,78 @@
+use crate::network::tests::fake::{scratch_dir, FakeTransport};
+use crate::{FileTokenStore, SupernovaBuilder, TokenStore};
+
+use std::fs;
+use std::sync::Arc;
+
+#[test]
+fn file_store_keys_tokens_by_upstream() {
+    let dir = scratch_dir("keys");
+    let store = FileTokenStore::new(dir.join("tokens.json"));
+
+    assert_eq!(store.load("https://a.example").unwrap(), None);
+    store.save("https://a.example", &"abc".to_string()).unwrap();
+    store.save("https://b.example", &"def".to_string()).unwrap();
+    assert_eq!(
+        store.load("https://a.example").unwrap().as_deref(),
+        Some("abc")
+    );
+
+    // Survives being reopened
+    let store = FileTokenStore::new(dir.join("tokens.json"));
+    store.remove("https://a.example").unwrap();
+    assert_eq!(store.load("https://a.example").unwrap(), None);
+    assert_eq!(
+        store.load("https://b.example").unwrap().as_deref(),
+        Some("def")
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn file_store_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch_dir("perms");
+    let store = FileTokenStore::new(dir.join("tokens.json"));
+    store.save("https://a.example", &"abc".to_string()).unwrap();
+
+    let mode = fs::metadata(store.path()).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn sessions_are_persisted_and_restored() {
+    let dir = scratch_dir("session");
+    let store = Arc::new(FileTokenStore::new(dir.join("tokens.json")));
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/login", 200, r#"{"token": "abc"}"#);
+    transport.respond("/api/logout", 200, "");
+    let build = || {
+        SupernovaBuilder::new()
+            .upstream("https://sn.example")
+            .transport(transport.clone())
+            .token_store(store.clone())
+            .build()
+    };
+
+    let client = build();
+    assert!(!client.restore_session().unwrap());
+    client.login("user", "pass").await.unwrap();
+    assert_eq!(
+        store.load("https://sn.example").unwrap().as_deref(),
+        Some("abc")
+    );
+
+    let restored = build();
+    assert!(restored.restore_session().unwrap());
+    assert!(restored.is_authenticated());
+
+    restored.logout().await.unwrap();
+    assert_eq!(store.load("https://sn.example").unwrap(), None);
+
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,236 @@
+use crate::network::tests::fake::{scratch_dir, FakeTransport};
+use crate::{
+    FileMirror, RemovalPolicy, RequestConfig, Supernova, ARCHIVE_DIR,
+    MANIFEST_NAME,
+};
+
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+const CLASS: &str = r#"
+{
+    "id": 449, "name": "Álgebra Linear: Teoria", "abbreviation": "AL",
+    "credits": 6, "department": null, "instances": [24772],
+    "url": "/faculdade/cadeira/449/", "external_id": 11156
+}"#;
+
+const CLASS_INSTANCE: &str = r#"
+{
+    "id": 24772, "parent": 449, "period": 3, "year": 2021,
+    "information": {"upstream": null}, "department": null,
+    "avg_grade": null, "enrollments": [], "shifts": [],
+    "url": "/faculdade/cadeira/i/24772/"
+}"#;
+
+const SLIDES: (&str, &str) =
+    ("slides v1", "3f44231d8385913a598660ed02213d8edc9e4dcc");
+const EXAM: (&str, &str) =
+    ("exame v1", "361d72b16e943707d83306a899eeae520779d1df");
+const NEW_EXAM: (&str, &str) =
+    ("exame v2", "c9628b5641fe6af7ebf69ff0e770fd57018f3c88");
+const COMMUNITY_SLIDES: (&str, &str) = (
+    "slides da comunidade",
+    "29ed99a11e8ad40546d50744be3c6319b45c598d",
+);
+
+fn file_json(
+    id: u32,
+    name: &str,
+    category: u8,
+    (content, hash): (&str, &str),
+) -> String {
+    format!(
+        r#"{{
+            "id": {id},
+            "file": {{
+                "hash": "{hash}", "size": {}, "mime": "application/pdf",
+                "license": "Todos os direitos reservados",
+                "url": "/faculdade/ficheiro/{hash}/"
+            }},
+            "name": "{name}", "category": {category},
+            "upload_datetime": "2021-03-24T02:06:49.551799Z",
+            "uploader": null, "uploader_teacher": null,
+            "url": "/faculdade/cadeira/i/24772/ficheiro/{id}"
+        }}"#,
+        content.len()
+    )
+}
+
+fn serve(transport: &FakeTransport, (content, hash): (&str, &str)) {
+    transport.respond(&format!("/faculdade/ficheiro/{hash}/"), 200, content);
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap()
+}
+
+#[tokio::test]
+async fn mirrors_only_what_changed() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/class/449", 200, CLASS);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        &format!(
+            r#"{{"official": [{}, {}], "community": [{}]}}"#,
+            file_json(1, "slides.pdf", 2, SLIDES),
+            file_json(2, "exame.pdf", 6, EXAM),
+            file_json(3, "resumo.pdf", 2, COMMUNITY_SLIDES),
+        ),
+    );
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        &format!(
+            r#"{{"official": [{}, {}], "community": []}}"#,
+            file_json(1, "slides.pdf", 2, SLIDES),
+            file_json(2, "exame.pdf", 6, NEW_EXAM),
+        ),
+    );
+    for file in [SLIDES, EXAM, NEW_EXAM, COMMUNITY_SLIDES] {
+        serve(&transport, file);
+    }
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+    let instance = client.get_class_instance(24772, &conf).await.unwrap();
+
+    let root = scratch_dir("mirror");
+    let mirror = FileMirror::new(&root);
+    let report = mirror
+        .sync(&client, std::slice::from_ref(&instance), &conf)
+        .await
+        .unwrap();
+    assert_eq!(report.downloaded.len(), 3);
+    assert!(report.failed.is_empty());
+
+    let dir = root
+        .join("Álgebra Linear_ Teoria")
+        .join("2021")
+        .join("Semester 2");
+    assert_eq!(read(&dir.join("Slides").join("slides.pdf")), SLIDES.0);
+    assert_eq!(
+        read(&dir.join("Slides").join("resumo.pdf")),
+        COMMUNITY_SLIDES.0
+    );
+    assert_eq!(read(&dir.join("Exams").join("exame.pdf")), EXAM.0);
+    assert!(root.join(MANIFEST_NAME).exists());
+
+    // The next listing has a new exam, and lacks the community slides
+    let requests = transport.request_count();
+    let conf = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    let report = mirror.sync(&client, &[instance], &conf).await.unwrap();
+    assert_eq!(report.unchanged, 1);
+    assert_eq!(report.downloaded, vec![dir.join("Exams").join("exame.pdf")]);
+    assert_eq!(report.removed, vec![dir.join("Slides").join("resumo.pdf")]);
+    // The class, the listing and the new exam
+    assert_eq!(transport.request_count(), requests + 3);
+
+    assert_eq!(read(&dir.join("Exams").join("exame.pdf")), NEW_EXAM.0);
+    assert!(!dir.join("Slides").join("resumo.pdf").exists());
+    let archived = root
+        .join(ARCHIVE_DIR)
+        .join("Álgebra Linear_ Teoria/2021/Semester 2/Slides/resumo.pdf");
+    assert_eq!(read(&archived), COMMUNITY_SLIDES.0);
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[tokio::test]
+async fn removed_files_can_be_deleted() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/class/449", 200, CLASS);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        &format!(
+            r#"{{"official": [{}], "community": []}}"#,
+            file_json(2, "exame.pdf", 6, EXAM),
+        ),
+    );
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        r#"{"official": [], "community": []}"#,
+    );
+    serve(&transport, EXAM);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    let instance = client.get_class_instance(24772, &conf).await.unwrap();
+
+    let root = scratch_dir("mirror_delete");
+    let mirror = FileMirror::new(&root).removal(RemovalPolicy::Delete);
+    mirror
+        .sync(&client, std::slice::from_ref(&instance), &conf)
+        .await
+        .unwrap();
+    let report = mirror.sync(&client, &[instance], &conf).await.unwrap();
+
+    assert_eq!(report.removed.len(), 1);
+    assert!(!report.removed[0].exists());
+    assert!(!root.join(ARCHIVE_DIR).exists());
+    fs::remove_dir_all(root).unwrap();
+}
+
+#[tokio::test]
+async fn files_named_alike_are_told_apart() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/class/449", 200, CLASS);
+    transport.respond("/api/class/i/24772", 200, CLASS_INSTANCE);
+    let official = file_json(1, "slides.pdf", 2, SLIDES);
+    let community = file_json(3, "slides.pdf", 2, COMMUNITY_SLIDES);
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        &format!(r#"{{"official": [{official}], "community": [{community}]}}"#),
+    );
+    // The same files, listed the other way around
+    transport.respond(
+        "/api/class/i/24772/files",
+        200,
+        &format!(
+            r#"{{"official": [], "community": [{community}, {official}]}}"#
+        ),
+    );
+    serve(&transport, SLIDES);
+    serve(&transport, COMMUNITY_SLIDES);
+    let client = Supernova::with_transport(transport);
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    let instance = client.get_class_instance(24772, &conf).await.unwrap();
+
+    let root = scratch_dir("mirror_alike");
+    let mirror = FileMirror::new(&root);
+    mirror
+        .sync(&client, std::slice::from_ref(&instance), &conf)
+        .await
+        .unwrap();
+    let dir = root
+        .join("Álgebra Linear_ Teoria")
+        .join("2021")
+        .join("Semester 2")
+        .join("Slides");
+    assert!(!dir.join("slides.pdf").exists());
+    assert_eq!(read(&dir.join("slides (1).pdf")), SLIDES.0);
+    assert_eq!(read(&dir.join("slides (3).pdf")), COMMUNITY_SLIDES.0);
+
+    let report = mirror.sync(&client, &[instance], &conf).await.unwrap();
+    assert_eq!(report.unchanged, 2);
+    assert!(report.downloaded.is_empty() && report.removed.is_empty());
+    fs::remove_dir_all(root).unwrap();
+}
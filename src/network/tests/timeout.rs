@@ -0,0 +1,106 @@
+use crate::errors::Error;
+use crate::network::retry::RetryPolicy;
+use crate::network::tests::fake::FakeTransport;
+use crate::network::transport::Timeouts;
+use crate::{RequestConfig, SupernovaBuilder};
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn slow_requests_time_out() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 200, "[]");
+    transport.stall(Duration::from_secs(5));
+    let client = SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::disabled())
+        .timeout(Duration::from_millis(10))
+        .build();
+
+    assert!(matches!(
+        client.get_buildings(&RequestConfig::default()).await,
+        Err(Error::Timeout)
+    ));
+}
+
+#[tokio::test]
+async fn timeouts_can_be_overridden_per_request() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 200, "[]");
+    transport.stall(Duration::from_millis(50));
+    let client = SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy::disabled())
+        .timeouts(Timeouts {
+            connect: Some(Duration::from_secs(1)),
+            read: Some(Duration::from_secs(2)),
+            total: Some(Duration::from_millis(10)),
+        })
+        .build();
+
+    let conf = RequestConfig {
+        evade_cache: true,
+        timeouts: Timeouts {
+            read: Some(Duration::from_secs(3)),
+            total: Some(Duration::from_secs(5)),
+            ..Timeouts::default()
+        },
+    };
+    assert!(client.get_buildings(&conf).await.is_ok());
+
+    let requests = transport.requests.lock().unwrap();
+    assert_eq!(
+        requests[0].timeouts,
+        Timeouts {
+            connect: Some(Duration::from_secs(1)),
+            read: Some(Duration::from_secs(3)),
+            total: Some(Duration::from_secs(5)),
+        }
+    );
+}
+
+#[tokio::test]
+async fn the_total_timeout_spans_every_attempt() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 200, "[]");
+    transport.stall(Duration::from_secs(5));
+    let client = SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            jitter: false,
+            ..RetryPolicy::default()
+        })
+        .timeout(Duration::from_millis(10))
+        .build();
+
+    assert!(matches!(
+        client.get_buildings(&RequestConfig::default()).await,
+        Err(Error::Timeout)
+    ));
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn the_total_timeout_includes_backoff() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 503, "");
+    let client = SupernovaBuilder::new()
+        .transport(transport.clone())
+        .retry_policy(RetryPolicy {
+            initial_backoff: Duration::from_secs(5),
+            jitter: false,
+            ..RetryPolicy::default()
+        })
+        .timeout(Duration::from_millis(50))
+        .build();
+
+    let start = Instant::now();
+    assert!(matches!(
+        client.get_buildings(&RequestConfig::default()).await,
+        Err(Error::Timeout)
+    ));
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(transport.request_count(), 1);
+}
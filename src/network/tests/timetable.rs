@@ -0,0 +1,253 @@
+use crate::keys::BuildingKey;
+use crate::models::{
+    ClassInstance, ClassShift, ClassShiftInstance, ShiftType, Weekday,
+};
+use crate::network::tests::fake::FakeTransport;
+use crate::timetable::{
+    clashes, cost, lower_bound, Clash, Criterion, Lesson, TimetablePlanner,
+};
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::NaiveTime;
+
+fn class_instance(id: u32, shifts: &str) -> String {
+    format!(
+        r#"{{
+            "id": {id}, "parent": 449, "period": 3, "year": 2021,
+            "information": {{"upstream": null}}, "department": null,
+            "avg_grade": null, "enrollments": [], "shifts": [{shifts}],
+            "url": "/faculdade/cadeira/i/{id}/"
+        }}"#
+    )
+}
+
+fn shift(
+    id: u32,
+    shift_type: u8,
+    weekday: u8,
+    start: u16,
+    room: Option<u32>,
+) -> String {
+    let room = room.map_or("null".to_string(), |room| room.to_string());
+    format!(
+        r#"{{
+            "id": {id}, "number": {id}, "type": {shift_type}, "teachers": [],
+            "instances": [{{
+                "weekday": {weekday}, "start": {start}, "duration": 120,
+                "room": {room}
+            }}]
+        }}"#
+    )
+}
+
+fn place(id: u32, building: u32) -> String {
+    format!(
+        r#"{{
+            "id": {id}, "name": "Sala {id}", "floor": 1,
+            "building": {building}, "picture": null, "cover": null,
+            "features": [], "room_meta": null
+        }}"#
+    )
+}
+
+/// Two class instances, the first with two theoretical and two practical
+/// shifts (the first of each clashing), the second with a single one
+async fn class_instances() -> (Arc<FakeTransport>, Vec<ClassInstance>) {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    let first = [
+        shift(1, 1, 0, 540, Some(10)),
+        shift(2, 1, 1, 840, Some(11)),
+        shift(3, 2, 0, 600, None),
+        shift(4, 2, 0, 660, Some(10)),
+    ]
+    .join(",");
+    transport.respond("/api/class/i/1", 200, &class_instance(1, &first));
+    transport.respond(
+        "/api/class/i/2",
+        200,
+        &class_instance(2, &shift(5, 1, 0, 840, Some(11))),
+    );
+    transport.respond(
+        "/api/places",
+        200,
+        &format!("[{}, {}]", place(10, 2), place(11, 3)),
+    );
+
+    let client = Supernova::with_transport(transport.clone());
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let conf = RequestConfig::default();
+    let class_instances = vec![
+        client.get_class_instance(1, &conf).await.unwrap(),
+        client.get_class_instance(2, &conf).await.unwrap(),
+    ];
+    (transport, class_instances)
+}
+
+fn shift_ids(shifts: &[crate::models::ClassShift]) -> Vec<u32> {
+    let mut ids: Vec<u32> = shifts.iter().map(|shift| shift.id).collect();
+    ids.sort_unstable();
+    ids
+}
+
+#[tokio::test]
+async fn timetables_are_ranked_by_criteria() {
+    let (transport, class_instances) = class_instances().await;
+    let requests = transport.request_count();
+
+    let timetables = TimetablePlanner::new()
+        .rank_by(Criterion::FreeDays)
+        .rank_by(Criterion::FewerBuildingChanges)
+        .plan(&class_instances)
+        .await
+        .unwrap();
+    // Shifts 1 and 3 clash
+    assert_eq!(timetables.len(), 3);
+    assert_eq!(shift_ids(&timetables[0].shifts), vec![1, 4, 5]);
+    assert_eq!(timetables[0].costs, vec![1, 1]);
+    assert_eq!(shift_ids(&timetables[1].shifts), vec![2, 3, 5]);
+    assert_eq!(timetables[1].costs, vec![2, 0]);
+    assert_eq!(shift_ids(&timetables[2].shifts), vec![2, 4, 5]);
+    assert_eq!(timetables[2].costs, vec![2, 1]);
+    // Places are fetched all at once
+    assert_eq!(transport.request_count(), requests + 1);
+
+    let best = TimetablePlanner::new()
+        .rank_by(Criterion::FreeDays)
+        .rank_by(Criterion::FewerBuildingChanges)
+        .limit(1)
+        .plan(&class_instances)
+        .await
+        .unwrap();
+    assert_eq!(best.len(), 1);
+    assert_eq!(shift_ids(&best[0].shifts), vec![1, 4, 5]);
+
+    let timetables = TimetablePlanner::new()
+        .rank_by(Criterion::NoEarlyMornings(
+            NaiveTime::from_hms_opt(10, 0, 0).unwrap(),
+        ))
+        .rank_by(Criterion::Compactness)
+        .limit(2)
+        .plan(&class_instances)
+        .await
+        .unwrap();
+    assert_eq!(timetables.len(), 2);
+    assert_eq!(shift_ids(&timetables[0].shifts), vec![2, 4, 5]);
+    assert_eq!(timetables[0].costs, vec![0, 60]);
+    assert_eq!(shift_ids(&timetables[1].shifts), vec![2, 3, 5]);
+    assert_eq!(timetables[1].costs, vec![0, 120]);
+}
+
+#[tokio::test]
+async fn places_are_only_fetched_when_needed() {
+    let (transport, class_instances) = class_instances().await;
+    let requests = transport.request_count();
+
+    TimetablePlanner::new()
+        .rank_by(Criterion::FreeDays)
+        .plan(&class_instances)
+        .await
+        .unwrap();
+    assert_eq!(transport.request_count(), requests);
+}
+
+fn shift_at(id: u32, weekday: Weekday, start: u16) -> ClassShift {
+    ClassShift {
+        id,
+        number: 1,
+        shift_type: ShiftType::Practical,
+        teachers: vec![],
+        instances: vec![ClassShiftInstance {
+            weekday,
+            start,
+            duration: 120,
+            room: None,
+        }],
+        class_instance: None,
+    }
+}
+
+fn lesson(
+    weekday: Weekday,
+    start: u32,
+    end: u32,
+    building: Option<BuildingKey>,
+) -> Lesson {
+    Lesson {
+        shift: 1,
+        weekday,
+        start,
+        end,
+        building,
+    }
+}
+
+#[test]
+fn clashes_are_detected() {
+    let shifts = [
+        shift_at(1, Weekday::Monday, 540),
+        shift_at(3, Weekday::Monday, 600),
+        shift_at(4, Weekday::Monday, 660),
+        shift_at(5, Weekday::Tuesday, 540),
+    ];
+    assert_eq!(
+        clashes(&shifts),
+        vec![
+            Clash {
+                first: 1,
+                second: 3,
+                weekday: Weekday::Monday
+            },
+            Clash {
+                first: 3,
+                second: 4,
+                weekday: Weekday::Monday
+            }
+        ]
+    );
+    // Lessons back to back do not clash
+    assert!(clashes(&[shifts[0].clone(), shifts[2].clone()]).is_empty());
+}
+
+#[test]
+fn costs() {
+    let lessons = [
+        lesson(Weekday::Monday, 480, 600, Some(2)),
+        lesson(Weekday::Monday, 840, 960, Some(3)),
+        lesson(Weekday::Monday, 960, 1020, None),
+        lesson(Weekday::Monday, 1020, 1080, Some(2)),
+        lesson(Weekday::Thursday, 600, 720, Some(3)),
+    ];
+    let nine = NaiveTime::from_hms_opt(9, 0, 0).unwrap();
+    assert_eq!(cost(Criterion::NoEarlyMornings(nine), &lessons), 1);
+    assert_eq!(cost(Criterion::FreeDays, &lessons), 2);
+    assert_eq!(cost(Criterion::Compactness, &lessons), 240);
+    // Lessons without a place don't count
+    assert_eq!(cost(Criterion::FewerBuildingChanges, &lessons), 2);
+}
+
+#[test]
+fn lower_bounds_hold_as_lessons_are_added() {
+    let some = [
+        lesson(Weekday::Monday, 480, 600, Some(2)),
+        lesson(Weekday::Monday, 840, 960, Some(3)),
+    ];
+    let mut more = some.to_vec();
+    more.push(lesson(Weekday::Monday, 600, 840, Some(4)));
+    let criteria = [
+        Criterion::NoEarlyMornings(NaiveTime::from_hms_opt(9, 0, 0).unwrap()),
+        Criterion::FreeDays,
+        Criterion::Compactness,
+        Criterion::FewerBuildingChanges,
+    ];
+    for criterion in criteria {
+        assert!(lower_bound(criterion, &some) <= cost(criterion, &more));
+    }
+    // Filling the gap made it more compact
+    assert!(
+        cost(Criterion::Compactness, &more)
+            < cost(Criterion::Compactness, &some)
+    );
+}
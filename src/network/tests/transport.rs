@@ -0,0 +1,98 @@
+use crate::errors::Error;
+use crate::network::tests::fake::FakeTransport;
+use crate::network::transport::Method;
+use crate::{RequestConfig, Supernova, SupernovaBuilder};
+
+use std::sync::Arc;
+
+const BUILDINGS: &str = r#"
+[
+    {
+        "id": 16,
+        "name": "Biblioteca",
+        "abbreviation": "Bibliot.",
+        "places": [],
+        "thumb": "/media/16.png",
+        "url": "/faculdade/campus/edificio/16/"
+    }
+]"#;
+
+#[tokio::test]
+async fn requests_go_through_the_transport() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 200, BUILDINGS);
+    let client = Supernova::with_transport(transport.clone());
+
+    let buildings = client
+        .get_buildings(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(buildings.len(), 1);
+    assert_eq!(buildings[0].name, "Biblioteca");
+
+    // The second time around the cache is used
+    client
+        .get_buildings(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(transport.request_count(), 1);
+
+    let request = &transport.requests.lock().unwrap()[0];
+    assert_eq!(request.method, Method::Get);
+    assert!(request.header("iron_planet").is_some());
+}
+
+#[tokio::test]
+async fn error_statuses_are_mapped() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/building/1", 404, "");
+    transport.respond("/api/building/2", 500, "");
+    let client = Supernova::with_transport(transport);
+    let conf = RequestConfig::default();
+
+    assert!(matches!(
+        client.get_building(1, &conf).await,
+        Err(Error::ResourceMissing(_))
+    ));
+    assert!(matches!(
+        client.get_building(2, &conf).await,
+        Err(Error::Server(_))
+    ));
+}
+
+#[tokio::test]
+async fn token_is_attached() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    let client = Supernova::with_transport(transport.clone());
+
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    assert!(client.is_authenticated());
+
+    let request = &transport.requests.lock().unwrap()[0];
+    assert_eq!(request.header("authorization"), Some("Token abc"));
+}
+
+#[tokio::test]
+async fn configured_upstream_is_followed() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/buildings", 200, BUILDINGS);
+    let client = SupernovaBuilder::new()
+        .upstream("http://localhost:8000/")
+        .user_agent("test_agent")
+        .transport(transport.clone())
+        .build();
+
+    let buildings = client
+        .get_buildings(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        buildings[0].thumb.as_deref(),
+        Some("http://localhost:8000/media/16.png")
+    );
+
+    let request = &transport.requests.lock().unwrap()[0];
+    assert_eq!(request.url, "http://localhost:8000/api/buildings");
+    assert_eq!(request.header("user-agent"), Some("test_agent"));
+}
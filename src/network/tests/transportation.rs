@@ -0,0 +1,110 @@
+use crate::models::TransportationMode;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::{Local, NaiveDate, NaiveTime};
+
+fn timetable(date: NaiveDate) -> String {
+    format!(
+        r#"{{
+    "date": "{date}",
+    "lines": [
+        {{
+            "id": 1, "name": "TST 124", "type": "bus",
+            "stops": [{{"id": 10, "name": "Campus"}}, {{"id": 11, "name": "Almada"}}],
+            "trips": [
+                {{"destination": "Almada", "stops": [
+                    {{"stop": 10, "time": "08:00:00"}},
+                    {{"stop": 11, "time": "08:20:00"}}
+                ]}},
+                {{"destination": "Campus", "stops": [
+                    {{"stop": 11, "time": "08:30:00"}},
+                    {{"stop": 10, "time": "08:50:00"}}
+                ]}},
+                {{"destination": "Almada", "stops": [
+                    {{"stop": 10, "time": "09:30:00"}},
+                    {{"stop": 11, "time": "09:50:00"}}
+                ]}}
+            ]
+        }},
+        {{
+            "id": 2, "name": "Cacilhas", "type": "ferry",
+            "stops": [{{"id": 10, "name": "Campus"}}, {{"id": 12, "name": "Cais do Sodré"}}],
+            "trips": [
+                {{"destination": null, "stops": [
+                    {{"stop": 10, "time": "09:00:00"}},
+                    {{"stop": 12, "time": "09:15:00"}}
+                ]}}
+            ]
+        }}
+    ]
+}}"#
+    )
+}
+
+fn hm(hour: u32, minute: u32) -> NaiveTime {
+    NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+}
+
+#[tokio::test]
+async fn next_departures_are_sorted_across_lines() {
+    let transport = Arc::new(FakeTransport::default());
+    let today = Local::now().date_naive();
+    transport.respond("/api/transportation/day", 200, &timetable(today));
+    let client = Supernova::with_transport(transport);
+
+    let day = client
+        .get_transportation_day(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(day.date, today);
+    assert_eq!(day.lines[1].mode, TransportationMode::Ferry);
+
+    let departures = day.next_departures(10, hm(8, 30));
+    let times: Vec<NaiveTime> = departures.iter().map(|dep| dep.time).collect();
+    // The trip arriving at 08:50 ends there
+    assert_eq!(times, vec![hm(9, 0), hm(9, 30)]);
+    assert_eq!(departures[0].line.name, "Cacilhas");
+    assert_eq!(departures[1].trip.arrival_time(), Some(hm(9, 50)));
+}
+
+#[tokio::test]
+async fn stops_are_listed_once() {
+    let transport = Arc::new(FakeTransport::default());
+    let today = Local::now().date_naive();
+    transport.respond("/api/transportation/day", 200, &timetable(today));
+    let client = Supernova::with_transport(transport);
+
+    let day = client
+        .get_transportation_day(&RequestConfig::default())
+        .await
+        .unwrap();
+    assert_eq!(day.stops().len(), 3);
+    let found = day.find_stops("cais");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].id, 12);
+}
+
+#[tokio::test]
+async fn timetable_is_cached_for_the_day() {
+    let transport = Arc::new(FakeTransport::default());
+    let today = Local::now().date_naive();
+    transport.respond("/api/transportation/day", 200, &timetable(today));
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    client.get_transportation_day(&conf).await.unwrap();
+    client.get_transportation_day(&conf).await.unwrap();
+    assert_eq!(transport.request_count(), 1);
+
+    let stale = Arc::new(FakeTransport::default());
+    let yesterday = today.pred_opt().unwrap();
+    stale.respond("/api/transportation/day", 200, &timetable(yesterday));
+    let client = Supernova::with_transport(stale.clone());
+
+    client.get_transportation_day(&conf).await.unwrap();
+    client.get_transportation_day(&conf).await.unwrap();
+    assert_eq!(stale.request_count(), 2);
+}
@@ -0,0 +1,128 @@
+use crate::errors::Error;
+use crate::models::GroupActivity;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+const USER: &str = r#"
+{
+    "id": 7, "nickname": "claudio", "name": "Cláudio Pereira",
+    "picture": "/media/u/7.png", "students": [12], "teacher": null,
+    "groups": [3],
+    "social_links": [
+        {"network": "GitHub", "url": "https://github.com/claudio"}
+    ],
+    "url": "/u/claudio"
+}"#;
+
+const GROUP: &str = r#"
+{
+    "id": 3, "name": "Núcleo de Informática", "abbreviation": "NI",
+    "type": 1, "url": "/g/ni", "thumb": null, "outsiders_openness": 3,
+    "official": true,
+    "activities": [
+        {
+            "resourcetype": "Announcement", "author": 7, "title": "Olá",
+            "content": "Bem-vindos", "datetime": "2022-05-02T10:00:00Z"
+        }
+    ],
+    "schedule_entries": [],
+    "events": []
+}"#;
+
+#[tokio::test]
+async fn profiles_are_fetched_and_cached() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/profile/7", 200, USER);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    let user = client.get_user(7, &conf).await.unwrap();
+    assert_eq!(user.nickname, "claudio");
+    assert_eq!(user.social_links[0].network, "GitHub");
+    assert!(user.picture.as_ref().unwrap().ends_with("/media/u/7.png"));
+    assert!(user.get_teacher().await.unwrap().is_none());
+
+    client.get_user(7, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 1);
+}
+
+#[tokio::test]
+async fn activity_authors_are_coerced() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/profile/7", 200, USER);
+    transport.respond("/api/group/3", 200, GROUP);
+    let client = Supernova::with_transport(transport);
+
+    let user = client.get_user(7, &RequestConfig::default()).await.unwrap();
+    let groups = user.get_groups().await.unwrap();
+    let activities = groups[0].activities().await.unwrap();
+    let GroupActivity::Announcement(announcement) = &activities[0] else {
+        panic!("Expected an announcement");
+    };
+    assert_eq!(announcement.author().await.unwrap(), user);
+}
+
+#[tokio::test]
+async fn question_authors_are_coerced() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/profile/7", 200, USER);
+    transport.respond(
+        "/api/learning/question/4",
+        200,
+        r#"{"id": 4, "title": "Derivada de x^x",
+            "content": "Como se deriva?", "user": 7,
+            "timestamp": "2021-10-03T14:00:00Z", "sections": [],
+            "answers": [
+                {"id": 5, "content": "Logaritmos", "user": null,
+                 "timestamp": "2021-10-03T15:00:00Z", "accepted": true}
+            ]}"#,
+    );
+    let client = Supernova::with_transport(transport);
+
+    let question = client
+        .get_question(4, &RequestConfig::default())
+        .await
+        .unwrap();
+    let author = question.author().await.unwrap().unwrap();
+    assert_eq!(author.nickname, "claudio");
+    assert!(question.answers[0].author().await.unwrap().is_none());
+}
+
+const ME: &str = r#"
+{
+    "id": 7, "nickname": "claudio", "name": "Cláudio Pereira",
+    "picture": null, "students": [12], "teacher": null, "groups": [],
+    "social_links": [], "url": "/u/claudio",
+    "permissions": ["feedback.add_review"]
+}"#;
+
+#[tokio::test]
+async fn me_is_cached_for_the_session() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/validation", 200, r#""Success""#);
+    transport.respond("/api/profile/me", 200, ME);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    assert!(matches!(
+        client.me(&conf).await,
+        Err(Error::MissingAuthentication)
+    ));
+
+    client.set_auth_token("abc".to_string()).await.unwrap();
+    let me = client.me(&conf).await.unwrap();
+    assert_eq!(me.profile.nickname, "claudio");
+    assert!(me.has_permission("feedback.add_review"));
+    assert!(!me.has_permission("feedback.delete_review"));
+    client.me(&conf).await.unwrap();
+    // The profile is also known by its key
+    client.get_user(7, &conf).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+
+    // Another session might belong to someone else
+    client.set_auth_token("def".to_string()).await.unwrap();
+    client.me(&conf).await.unwrap();
+    assert_eq!(transport.request_count(), 4);
+}
@@ -0,0 +1,76 @@
+use crate::models::WeatherCondition;
+use crate::network::tests::fake::FakeTransport;
+use crate::{RequestConfig, Supernova};
+
+use std::sync::Arc;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+
+const WEATHER: &str = r#"
+{
+    "current": {
+        "time": "2022-05-04T10:00:00Z", "temperature": 18.5, "humidity": 70,
+        "precipitation": 0.0, "wind_speed": 12.0, "wind_direction": 270,
+        "condition": 3
+    },
+    "hourly": [
+        {
+            "time": "2022-05-04T10:00:00Z", "temperature": 18.5,
+            "precipitation": 0.0, "precipitation_probability": 5,
+            "wind_speed": 12.0, "wind_direction": 270, "condition": 3
+        },
+        {
+            "time": "2022-05-04T11:00:00Z", "temperature": 19.0,
+            "precipitation": 1.5, "precipitation_probability": 60,
+            "wind_speed": 15.0, "wind_direction": null, "condition": 9
+        }
+    ],
+    "daily": [
+        {
+            "date": "2022-05-04", "min_temperature": 12.0,
+            "max_temperature": 21.0, "precipitation": 3.0,
+            "precipitation_probability": 60, "wind_speed": 15.0,
+            "condition": 99
+        }
+    ]
+}"#;
+
+#[tokio::test]
+async fn weather_is_typed() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/weather", 200, WEATHER);
+    let client = Supernova::with_transport(transport);
+
+    let weather = client.get_weather(&RequestConfig::default()).await.unwrap();
+    assert_eq!(weather.current.condition, WeatherCondition::PartlyCloudy);
+    assert_eq!(weather.current.humidity, Some(70));
+
+    let later = Utc.with_ymd_and_hms(2022, 5, 4, 11, 45, 0).unwrap();
+    let forecast = weather.forecast_at(later).unwrap();
+    assert_eq!(forecast.condition, WeatherCondition::Rain);
+    assert!(forecast.condition.is_wet());
+
+    let date = NaiveDate::from_ymd_opt(2022, 5, 4).unwrap();
+    let daily = weather.forecast_on(date).unwrap();
+    assert_eq!(daily.condition, WeatherCondition::Unknown(99));
+    assert!(weather.forecast_on(date.succ_opt().unwrap()).is_none());
+}
+
+#[tokio::test]
+async fn weather_is_cached() {
+    let transport = Arc::new(FakeTransport::default());
+    transport.respond("/api/weather", 200, WEATHER);
+    let client = Supernova::with_transport(transport.clone());
+    let conf = RequestConfig::default();
+
+    client.get_weather(&conf).await.unwrap();
+    client.get_weather(&conf).await.unwrap();
+    assert_eq!(transport.request_count(), 1);
+
+    let evasive = RequestConfig {
+        evade_cache: true,
+        ..RequestConfig::default()
+    };
+    client.get_weather(&evasive).await.unwrap();
+    assert_eq!(transport.request_count(), 2);
+}
@@ -0,0 +1,310 @@
+use crate::errors::Error;
+
+use std::collections::HashMap;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::{Body, Client};
+use hyper_tls::HttpsConnector;
+use tokio::time::timeout;
+
+type HttpClient = Client<HttpsConnector<HttpConnector>>;
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Method {
+    Get,
+    Post,
+    Put,
+    Patch,
+    Delete,
+}
+
+impl Method {
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Method::Get => "GET",
+            Method::Post => "POST",
+            Method::Put => "PUT",
+            Method::Patch => "PATCH",
+            Method::Delete => "DELETE",
+        }
+    }
+}
+
+impl fmt::Display for Method {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Bounds on how long requests may take. `None` leaves a bound unset.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct Timeouts {
+    /// Maximum duration of the establishment of a connection
+    pub connect: Option<Duration>,
+    /// Maximum duration of any wait for data from the server
+    pub read: Option<Duration>,
+    /// Maximum duration of a whole request, from start to end,
+    /// retries and the waits between them included
+    pub total: Option<Duration>,
+}
+
+impl Timeouts {
+    /// These timeouts, with the unset ones taken from `fallback`
+    #[must_use]
+    pub fn or(self, fallback: Timeouts) -> Timeouts {
+        Timeouts {
+            connect: self.connect.or(fallback.connect),
+            read: self.read.or(fallback.read),
+            total: self.total.or(fallback.total),
+        }
+    }
+}
+
+/// A request, as handed over to a `Transport`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+    /// The `total` timeout is enforced by the client itself.
+    /// Transports are expected to honor the others.
+    pub timeouts: Timeouts,
+}
+
+/// A response, as handed back by a `Transport`
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl HttpRequest {
+    /// The value of the first header named `name` (case insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+impl HttpResponse {
+    /// The value of the first header named `name` (case insensitive)
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+fn find_header<'a>(
+    headers: &'a [(String, String)],
+    name: &str,
+) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+/// The means through which a client reaches the server.
+///
+/// Every request of a `Supernova` client goes through its transport, which
+/// by default is a `HyperTransport`. Implementing this allows the client to
+/// run against something other than the network (eg. canned responses).
+///
+/// A transport is only expected to fail when no response could be obtained.
+/// Responses with error status codes are to be returned as any other.
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error>;
+
+    /// As `send`, but hands the body of successful (2xx) responses over to
+    /// `sink` as it arrives, instead of returning it.
+    /// Bodies of other responses are returned as usual.
+    ///
+    /// By default, bodies are received as a whole through `send`.
+    async fn send_streaming(
+        &self,
+        request: HttpRequest,
+        sink: &mut BodySink<'_>,
+    ) -> Result<HttpResponse, Error> {
+        let mut response = self.send(request).await?;
+        if (200..300).contains(&response.status) {
+            sink(&response.body)?;
+            response.body.clear();
+        }
+        Ok(response)
+    }
+}
+
+/// Receives a response body, one chunk at a time
+pub type BodySink<'a> = dyn FnMut(&[u8]) -> Result<(), Error> + Send + 'a;
+
+/// Tuning of the connection pool shared by every request of a client
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// Maximum amount of idle (kept-alive) connections kept for each host
+    pub max_idle_per_host: usize,
+    /// How long an idle connection is kept before being closed.
+    /// `None` keeps idle connections open indefinitely.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        PoolConfig {
+            max_idle_per_host: 16,
+            idle_timeout: Some(Duration::from_secs(90)),
+        }
+    }
+}
+
+/// The default transport, backed by a pooled `hyper` client.
+///
+/// As the connect timeout is a property of the pool's connector, requests
+/// with distinct connect timeouts are served by distinct pools.
+pub struct HyperTransport {
+    config: PoolConfig,
+    clients: Mutex<HashMap<Option<Duration>, HttpClient>>,
+}
+
+impl HyperTransport {
+    #[must_use]
+    pub fn new(config: &PoolConfig) -> HyperTransport {
+        HyperTransport {
+            config: config.clone(),
+            clients: Mutex::default(),
+        }
+    }
+
+    fn client(&self, connect_timeout: Option<Duration>) -> HttpClient {
+        self.clients
+            .lock()
+            .unwrap()
+            .entry(connect_timeout)
+            .or_insert_with(|| {
+                let mut http = HttpConnector::new();
+                http.enforce_http(false);
+                http.set_connect_timeout(connect_timeout);
+                let https = HttpsConnector::new_with_connector(http);
+                Client::builder()
+                    .pool_max_idle_per_host(self.config.max_idle_per_host)
+                    .pool_idle_timeout(self.config.idle_timeout)
+                    .build::<_, Body>(https)
+            })
+            .clone()
+    }
+}
+
+impl Default for HyperTransport {
+    fn default() -> Self {
+        HyperTransport::new(&PoolConfig::default())
+    }
+}
+
+#[async_trait]
+impl Transport for HyperTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse, Error> {
+        self.exchange(request, None).await
+    }
+
+    async fn send_streaming(
+        &self,
+        request: HttpRequest,
+        sink: &mut BodySink<'_>,
+    ) -> Result<HttpResponse, Error> {
+        self.exchange(request, Some(sink)).await
+    }
+}
+
+impl HyperTransport {
+    async fn exchange(
+        &self,
+        request: HttpRequest,
+        mut sink: Option<&mut BodySink<'_>>,
+    ) -> Result<HttpResponse, Error> {
+        let mut builder = hyper::Request::builder()
+            .uri(&request.url)
+            .method(request.method.as_str());
+        for (key, value) in &request.headers {
+            builder = builder.header(key, value);
+        }
+        let hyper_request = builder
+            .body(Body::from(request.body))
+            .map_err(|_| Error::Request)?;
+        let read_timeout = request.timeouts.read;
+
+        let exchange =
+            self.client(request.timeouts.connect).request(hyper_request);
+        let response = within(read_timeout, exchange)
+            .await?
+            .map_err(network_error)?;
+
+        let status = response.status().as_u16();
+        let headers = response
+            .headers()
+            .iter()
+            .filter_map(|(key, value)| {
+                value
+                    .to_str()
+                    .ok()
+                    .map(|value| (key.to_string(), value.to_string()))
+            })
+            .collect();
+        if !(200..300).contains(&status) {
+            sink = None;
+        }
+        let mut body = vec![];
+        let mut stream = response.into_body();
+        while let Some(chunk) = within(read_timeout, stream.data()).await? {
+            let chunk = chunk.map_err(network_error)?;
+            match &mut sink {
+                Some(sink) => sink(&chunk)?,
+                None => body.extend_from_slice(&chunk),
+            }
+        }
+
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+async fn within<F: std::future::Future>(
+    duration: Option<Duration>,
+    future: F,
+) -> Result<F::Output, Error> {
+    if let Some(duration) = duration {
+        timeout(duration, future).await.map_err(|_| Error::Timeout)
+    } else {
+        Ok(future.await)
+    }
+}
+
+fn network_error(err: hyper::Error) -> Error {
+    // Connect timeouts surface as I/O errors somewhere down the chain
+    let mut source = err.source();
+    while let Some(cause) = source {
+        if let Some(io_err) = cause.downcast_ref::<io::Error>() {
+            if io_err.kind() == io::ErrorKind::TimedOut {
+                return Error::Timeout;
+            }
+        }
+        source = cause.source();
+    }
+    if err.is_timeout() {
+        Error::Timeout
+    } else {
+        Error::Network(Some(Box::new(err)))
+    }
+}
@@ -0,0 +1,266 @@
+//! Room occupancy as per the shifts of a term, from which free rooms and
+//! weekly room schedules are found.
+
+use crate::errors::Error;
+use crate::keys::{BuildingKey, ClassInstanceKey, PlaceKey};
+use crate::models::{
+    Class, ClassInstance, ClassShift, Period, Place, PlaceVariant, RoomType,
+    Teacher, Weekday,
+};
+use crate::{RequestConfig, Supernova};
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use chrono::{NaiveTime, TimeDelta};
+use futures::stream::{self, StreamExt, TryStreamExt};
+
+/// A weekly lesson taking place in a room
+#[derive(Debug, Clone)]
+pub struct Occupation {
+    pub class_instance: ClassInstanceKey,
+    pub shift: ClassShift,
+    pub weekday: Weekday,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// When rooms are taken by lessons, throughout the week
+#[derive(Debug, Clone, Default)]
+pub struct RoomOccupancy {
+    occupations: HashMap<PlaceKey, Vec<Occupation>>,
+}
+
+/// A weekly lesson, as shown in the schedule of its room
+#[derive(Debug, Clone)]
+pub struct ScheduledLesson {
+    pub class: Class,
+    pub shift: ClassShift,
+    pub teachers: Vec<Teacher>,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+/// The lessons taking place in a room throughout the week
+#[derive(Debug, Clone, Default)]
+pub struct RoomSchedule {
+    /// Lessons of each weekday, sorted by time
+    pub days: BTreeMap<Weekday, Vec<ScheduledLesson>>,
+}
+
+/// What a room is to be like
+#[derive(Debug, Clone, Default)]
+pub struct RoomFilter {
+    building: Option<BuildingKey>,
+    min_capacity: Option<u16>,
+    room_type: Option<RoomType>,
+}
+
+impl RoomOccupancy {
+    /// The occupancy due to the shifts of `class_instances`
+    pub async fn new(
+        class_instances: &[ClassInstance],
+    ) -> Result<RoomOccupancy, Error> {
+        let mut occupations: HashMap<PlaceKey, Vec<Occupation>> =
+            HashMap::new();
+        for class_inst in class_instances {
+            for shift in class_inst.get_shifts().await? {
+                for instance in &shift.instances {
+                    let Some(room_ref) = &instance.room else {
+                        continue;
+                    };
+                    let start = instance.start_time();
+                    occupations.entry(room_ref.identifier).or_default().push(
+                        Occupation {
+                            class_instance: class_inst.id,
+                            shift: shift.clone(),
+                            weekday: instance.weekday,
+                            start,
+                            end: start
+                                + TimeDelta::minutes(i64::from(
+                                    instance.duration,
+                                )),
+                        },
+                    );
+                }
+            }
+        }
+        for room_occupations in occupations.values_mut() {
+            room_occupations.sort_by_key(|occupation| {
+                (occupation.weekday, occupation.start)
+            });
+        }
+        Ok(RoomOccupancy { occupations })
+    }
+
+    /// The lessons taking place in `room`, sorted by weekday and time
+    #[must_use]
+    pub fn of(&self, room: PlaceKey) -> &[Occupation] {
+        self.occupations.get(&room).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether `room` is free from `start` to `end` on `weekday`
+    #[must_use]
+    pub fn is_free(
+        &self,
+        room: PlaceKey,
+        weekday: Weekday,
+        start: NaiveTime,
+        end: NaiveTime,
+    ) -> bool {
+        !self.of(room).iter().any(|occupation| {
+            occupation.weekday == weekday
+                && occupation.start < end
+                && start < occupation.end
+        })
+    }
+}
+
+impl RoomSchedule {
+    /// The lessons on `weekday`, sorted by time
+    #[must_use]
+    pub fn on(&self, weekday: Weekday) -> &[ScheduledLesson] {
+        self.days.get(&weekday).map_or(&[], Vec::as_slice)
+    }
+
+    /// Lays out the lessons of `room` as per `occupancy`
+    pub async fn new(
+        client: &Arc<Supernova>,
+        occupancy: &RoomOccupancy,
+        room: PlaceKey,
+        conf: &RequestConfig,
+    ) -> Result<RoomSchedule, Error> {
+        let mut days: BTreeMap<Weekday, Vec<ScheduledLesson>> = BTreeMap::new();
+        for occupation in occupancy.of(room) {
+            let class = client
+                .get_class_instance(occupation.class_instance, conf)
+                .await?
+                .get_class()
+                .await?;
+            days.entry(occupation.weekday)
+                .or_default()
+                .push(ScheduledLesson {
+                    class,
+                    shift: occupation.shift.clone(),
+                    teachers: occupation.shift.get_teachers().await?,
+                    start: occupation.start,
+                    end: occupation.end,
+                });
+        }
+        Ok(RoomSchedule { days })
+    }
+}
+
+impl RoomFilter {
+    #[must_use]
+    pub fn new() -> RoomFilter {
+        RoomFilter::default()
+    }
+
+    #[must_use]
+    pub fn in_building(mut self, building: BuildingKey) -> RoomFilter {
+        self.building = Some(building);
+        self
+    }
+
+    /// Rooms of unknown capacity are left out
+    #[must_use]
+    pub fn min_capacity(mut self, capacity: u16) -> RoomFilter {
+        self.min_capacity = Some(capacity);
+        self
+    }
+
+    #[must_use]
+    pub fn room_type(mut self, room_type: RoomType) -> RoomFilter {
+        self.room_type = Some(room_type);
+        self
+    }
+
+    /// Whether `place` is a room in use that fits this filter
+    #[must_use]
+    pub fn matches(&self, place: &Place) -> bool {
+        let PlaceVariant::Room(room) = &place.variant else {
+            return false;
+        };
+        !room.extinguished
+            && self.building.is_none_or(|building| {
+                place.building.as_ref().is_some_and(|building_ref| {
+                    building_ref.identifier == building
+                })
+            })
+            && self.min_capacity.is_none_or(|min_capacity| {
+                room.capacity
+                    .is_some_and(|capacity| capacity >= min_capacity)
+            })
+            && self
+                .room_type
+                .is_none_or(|room_type| room.room_type == room_type)
+    }
+}
+
+impl Supernova {
+    /// The occupancy due to every class instance of the `period` of `year`.
+    ///
+    /// This needs every instance of every class to be known, which takes a
+    /// request per instance not fetched before. Those are made a few at a
+    /// time, as many as set with `SupernovaBuilder::concurrent_fetches`.
+    pub async fn room_occupancy(
+        self: &Arc<Supernova>,
+        year: u32,
+        period: Period,
+        conf: &RequestConfig,
+    ) -> Result<RoomOccupancy, Error> {
+        let classes = self.get_classes(conf).await?;
+        let mut class_instances = vec![];
+        let mut missing = vec![];
+        {
+            // Acquire read lock
+            let cache = self.cache.read().unwrap();
+            for class_inst_ref in
+                classes.iter().flat_map(|class| &class.instances)
+            {
+                match cache.class_instances.get(&class_inst_ref.identifier) {
+                    Some(net_class_inst) if !conf.evade_cache => {
+                        class_instances.push(net_class_inst.link(self.clone()));
+                    }
+                    _ => missing.push(class_inst_ref.identifier),
+                }
+            }
+        } // Drop read lock
+
+        let fetched: Vec<ClassInstance> = stream::iter(missing)
+            .map(|id| self.get_class_instance(id, conf))
+            .buffer_unordered(self.concurrent_fetches)
+            .try_collect()
+            .await?;
+        class_instances.extend(fetched);
+        class_instances.retain(|class_inst| {
+            class_inst.year == year && class_inst.period == period
+        });
+        RoomOccupancy::new(&class_instances).await
+    }
+
+    /// The rooms matching `filter` which are free from `start` to `end` on
+    /// `weekday`, as per `occupancy`
+    pub async fn free_rooms(
+        self: &Arc<Supernova>,
+        occupancy: &RoomOccupancy,
+        weekday: Weekday,
+        start: NaiveTime,
+        end: NaiveTime,
+        filter: &RoomFilter,
+        conf: &RequestConfig,
+    ) -> Result<Vec<Place>, Error> {
+        let mut rooms: Vec<Place> = self
+            .get_places(conf)
+            .await?
+            .into_iter()
+            .filter(|place| {
+                filter.matches(place)
+                    && occupancy.is_free(place.id, weekday, start, end)
+            })
+            .collect();
+        rooms.sort_by_key(|place| place.id);
+        Ok(rooms)
+    }
+}
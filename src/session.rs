@@ -0,0 +1,148 @@
+use crate::errors::Error;
+use crate::network::models::AuthToken;
+
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Somewhere to keep authentication tokens between runs.
+///
+/// Tokens are keyed by the upstream they belong to, so that a token is never
+/// sent to a server other than the one that issued it.
+pub trait TokenStore: Send + Sync {
+    /// The token stored for `upstream`, if any
+    fn load(&self, upstream: &str) -> Result<Option<AuthToken>, Error>;
+    fn save(&self, upstream: &str, token: &AuthToken) -> Result<(), Error>;
+    /// Forgets the token stored for `upstream`, if any
+    fn remove(&self, upstream: &str) -> Result<(), Error>;
+}
+
+/// Stores tokens in a JSON file that only its owner can access
+pub struct FileTokenStore {
+    path: PathBuf,
+    // Serializes read-modify-write cycles within the process
+    lock: Mutex<()>,
+}
+
+impl FileTokenStore {
+    #[must_use]
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileTokenStore {
+        FileTokenStore {
+            path: path.into(),
+            lock: Mutex::default(),
+        }
+    }
+
+    /// A store in the user's configuration directory
+    /// (eg. `~/.config/iron_planet/tokens.json`), if it can be determined.
+    #[must_use]
+    pub fn in_config_dir() -> Option<FileTokenStore> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| {
+                env::var_os("HOME").map(|home| Path::new(&home).join(".config"))
+            })?;
+        Some(FileTokenStore::new(
+            config_dir.join("iron_planet").join("tokens.json"),
+        ))
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn read(&self) -> Result<HashMap<String, AuthToken>, Error> {
+        match fs::read(&self.path) {
+            Ok(data) => {
+                serde_json::from_slice(&data).map_err(Error::Deserialization)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Ok(HashMap::new())
+            }
+            Err(err) => Err(Error::Io(err)),
+        }
+    }
+
+    /// Replaces the file as a whole, so that it is never left half-written
+    fn write(&self, tokens: &HashMap<String, AuthToken>) -> Result<(), Error> {
+        let data = serde_json::to_vec(tokens).map_err(Error::Serialization)?;
+        if let Some(dir) = self.path.parent() {
+            create_private_dir(dir).map_err(Error::Io)?;
+        }
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+
+        let mut file = create_private_file(&tmp_path).map_err(Error::Io)?;
+        file.write_all(&data)
+            .and_then(|()| file.sync_all())
+            .and_then(|()| fs::rename(&tmp_path, &self.path))
+            .map_err(|err| {
+                let _ = fs::remove_file(&tmp_path);
+                Error::Io(err)
+            })
+    }
+}
+
+impl TokenStore for FileTokenStore {
+    fn load(&self, upstream: &str) -> Result<Option<AuthToken>, Error> {
+        let _guard = self.lock.lock().unwrap();
+        Ok(self.read()?.remove(upstream))
+    }
+
+    fn save(&self, upstream: &str, token: &AuthToken) -> Result<(), Error> {
+        let _guard = self.lock.lock().unwrap();
+        let mut tokens = self.read()?;
+        tokens.insert(upstream.to_string(), token.clone());
+        self.write(&tokens)
+    }
+
+    fn remove(&self, upstream: &str) -> Result<(), Error> {
+        let _guard = self.lock.lock().unwrap();
+        let mut tokens = self.read()?;
+        if tokens.remove(upstream).is_some() {
+            self.write(&tokens)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn create_private_file(path: &Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?;
+    // The mode is only applied to new files
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(file)
+}
+
+#[cfg(not(unix))]
+fn create_private_file(path: &Path) -> io::Result<fs::File> {
+    fs::File::create(path)
+}
@@ -0,0 +1,301 @@
+//! Incremental mirroring of the files of class instances to a local
+//! directory.
+
+use crate::errors::Error;
+use crate::keys::ClassInstanceKey;
+use crate::models::{ClassInstance, File, FileCategory, Period};
+use crate::{RequestConfig, Supernova};
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+
+/// Name of the manifest, kept at the root of the mirror
+pub const MANIFEST_NAME: &str = ".manifest.json";
+/// Name of the directory, at the root of the mirror,
+/// into which files removed upstream get archived
+pub const ARCHIVE_DIR: &str = ".archive";
+
+/// What to do with mirrored files once they are gone upstream
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RemovalPolicy {
+    Delete,
+    /// Moves them into the archive directory, under the same relative path
+    Archive,
+    /// Leaves them be, but stops tracking them
+    Keep,
+}
+
+/// A local copy of the files of class instances, laid out as
+/// `<class>/<year>/<period>/<category>/<file>`.
+///
+/// A manifest of the mirrored files is kept alongside them, such that each
+/// sync only downloads the files which are new or changed since the last.
+pub struct FileMirror {
+    root: PathBuf,
+    removal: RemovalPolicy,
+}
+
+/// The outcome of a sync
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    /// Files that were new or had changed
+    pub downloaded: Vec<PathBuf>,
+    /// How many files were already up to date
+    pub unchanged: usize,
+    /// Files that are gone upstream, handled as per the removal policy
+    pub removed: Vec<PathBuf>,
+    /// Files that could not be downloaded, archived or deleted.
+    /// These are attempted again on the next sync.
+    pub failed: Vec<(PathBuf, Error)>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct Manifest {
+    /// Entries by their path, relative to the root and separated by slashes
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+struct ManifestEntry {
+    id: u32,
+    class_instance: ClassInstanceKey,
+    hash: String,
+    size: u32,
+}
+
+impl FileMirror {
+    /// A mirror at `root`, which archives removed files
+    #[must_use]
+    pub fn new<P: Into<PathBuf>>(root: P) -> FileMirror {
+        FileMirror {
+            root: root.into(),
+            removal: RemovalPolicy::Archive,
+        }
+    }
+
+    #[must_use]
+    pub fn removal(mut self, policy: RemovalPolicy) -> FileMirror {
+        self.removal = policy;
+        self
+    }
+
+    #[must_use]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Brings the mirrored files of `instances` up to date.
+    ///
+    /// Every listing is fetched before anything is touched, such that a
+    /// failure to list leaves the mirror as it was.
+    /// Files of instances other than `instances` are left alone.
+    pub async fn sync(
+        &self,
+        client: &Arc<Supernova>,
+        instances: &[ClassInstance],
+        conf: &RequestConfig,
+    ) -> Result<SyncReport, Error> {
+        let mut wanted: BTreeMap<String, (ManifestEntry, File)> =
+            BTreeMap::new();
+        for instance in instances {
+            let class =
+                client.get_class(instance.parent.identifier, conf).await?;
+            let files =
+                client.get_class_instance_files(instance.id, conf).await?;
+            let dir = format!(
+                "{}/{}/{}",
+                sanitize(&class.name),
+                instance.year,
+                period_dir(instance.period)
+            );
+            let files: Vec<_> = files
+                .official
+                .iter()
+                .chain(&files.community)
+                .map(|file| {
+                    let category = category_dir(file.category);
+                    (format!("{dir}/{category}"), sanitize(&file.name), file)
+                })
+                .collect();
+            let mut name_count: HashMap<(&str, &str), usize> = HashMap::new();
+            for (category_path, name, _) in &files {
+                *name_count.entry((category_path, name)).or_default() += 1;
+            }
+            for (category_path, name, file) in &files {
+                // Tell apart distinct files with the same name, all of them
+                // so that their paths do not depend on the listing order
+                let key = (category_path.as_str(), name.as_str());
+                let path = if name_count[&key] > 1 {
+                    format!("{category_path}/{}", with_id(name, file.id))
+                } else {
+                    format!("{category_path}/{name}")
+                };
+                let entry = ManifestEntry {
+                    id: file.id,
+                    class_instance: instance.id,
+                    hash: file.file.hash.clone(),
+                    size: file.file.size,
+                };
+                wanted.insert(path, (entry, file.file.clone()));
+            }
+        }
+
+        let mut manifest = self.read_manifest()?;
+        let mut report = SyncReport::default();
+
+        let synced: HashSet<ClassInstanceKey> =
+            instances.iter().map(|instance| instance.id).collect();
+        let stale: Vec<String> = manifest
+            .files
+            .iter()
+            .filter(|(path, entry)| {
+                synced.contains(&entry.class_instance)
+                    && !wanted.contains_key(*path)
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in stale {
+            let local = self.local_path(&path);
+            match self.remove(&path, &local) {
+                Ok(()) => {
+                    manifest.files.remove(&path);
+                    report.removed.push(local);
+                }
+                Err(err) => report.failed.push((local, Error::Io(err))),
+            }
+        }
+
+        for (path, (entry, file)) in wanted {
+            let local = self.local_path(&path);
+            let up_to_date = manifest.files.get(&path) == Some(&entry)
+                && fs::metadata(&local)
+                    .is_ok_and(|meta| meta.len() == u64::from(entry.size));
+            if up_to_date {
+                report.unchanged += 1;
+                continue;
+            }
+            match client.download_file(&file, &local, |_| {}, conf).await {
+                Ok(()) => {
+                    manifest.files.insert(path, entry);
+                    report.downloaded.push(local);
+                }
+                Err(err) => report.failed.push((local, err)),
+            }
+        }
+
+        self.write_manifest(&manifest)?;
+        Ok(report)
+    }
+
+    fn local_path(&self, path: &str) -> PathBuf {
+        path.split('/')
+            .fold(self.root.clone(), |local, part| local.join(part))
+    }
+
+    fn remove(&self, path: &str, local: &Path) -> io::Result<()> {
+        let result = match self.removal {
+            RemovalPolicy::Delete => fs::remove_file(local),
+            RemovalPolicy::Archive => {
+                let archived =
+                    self.local_path(&format!("{ARCHIVE_DIR}/{path}"));
+                if let Some(dir) = archived.parent() {
+                    fs::create_dir_all(dir)?;
+                }
+                fs::rename(local, archived)
+            }
+            RemovalPolicy::Keep => Ok(()),
+        };
+        match result {
+            // Removed by someone else in the meantime
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+            result => result,
+        }
+    }
+
+    fn read_manifest(&self) -> Result<Manifest, Error> {
+        match fs::read(self.root.join(MANIFEST_NAME)) {
+            Ok(data) => {
+                serde_json::from_slice(&data).map_err(Error::Deserialization)
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                Ok(Manifest::default())
+            }
+            Err(err) => Err(Error::Io(err)),
+        }
+    }
+
+    /// Replaces the manifest as a whole, so that it is never left half-written
+    fn write_manifest(&self, manifest: &Manifest) -> Result<(), Error> {
+        let data = serde_json::to_vec_pretty(manifest)
+            .map_err(Error::Serialization)?;
+        fs::create_dir_all(&self.root).map_err(Error::Io)?;
+        let path = self.root.join(MANIFEST_NAME);
+        let tmp_path = self.root.join(format!("{MANIFEST_NAME}.tmp"));
+        fs::write(&tmp_path, data)
+            .and_then(|()| fs::rename(&tmp_path, path))
+            .map_err(Error::Io)
+    }
+}
+
+/// Name of the directory of the files of a `period`
+fn period_dir(period: Period) -> &'static str {
+    match period {
+        Period::Year => "Year",
+        Period::FirstSemester => "Semester 1",
+        Period::SecondSemester => "Semester 2",
+        Period::FirstTrimester => "Trimester 1",
+        Period::SecondTrimester => "Trimester 2",
+        Period::ThirdTrimester => "Trimester 3",
+        Period::FourthTrimester => "Trimester 4",
+    }
+}
+
+/// Name of the directory of the files of a `category`
+fn category_dir(category: FileCategory) -> &'static str {
+    match category {
+        FileCategory::Image => "Images",
+        FileCategory::Slides => "Slides",
+        FileCategory::Problems => "Problems",
+        FileCategory::Protocol => "Protocols",
+        FileCategory::Seminar => "Seminars",
+        FileCategory::Exam => "Exams",
+        FileCategory::Test => "Tests",
+        FileCategory::Support => "Support",
+        FileCategory::Others => "Others",
+    }
+}
+
+/// `name`, made safe to use as a single path component
+fn sanitize(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    // Hidden names would clash with the manifest and archive
+    let name = name.trim_start_matches('.');
+    if name.is_empty() {
+        "_".to_string()
+    } else {
+        name.to_string()
+    }
+}
+
+/// `name` with `id` right before its extension (eg. `notes (12).pdf`)
+fn with_id(name: &str, id: u32) -> String {
+    match name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => {
+            format!("{stem} ({id}).{extension}")
+        }
+        _ => format!("{name} ({id})"),
+    }
+}
@@ -0,0 +1,365 @@
+//! Planning of timetables: every choice of one shift per shift type of a
+//! set of class instances without clashes, ranked by criteria such as free
+//! days, compactness, early mornings or building changes.
+
+use crate::errors::Error;
+use crate::keys::{BuildingKey, ClassInstanceKey, PlaceKey, ShiftKey};
+use crate::models::{ClassInstance, ClassShift, ShiftType, Weekday};
+
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use chrono::{NaiveTime, Timelike};
+
+/// What makes a timetable better than another.
+/// Every criterion is a cost, such that lower is better.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum Criterion {
+    /// Fewer lessons starting before the given time
+    NoEarlyMornings(NaiveTime),
+    /// Fewer days with lessons
+    FreeDays,
+    /// Less time idling between the lessons of each day, in minutes
+    Compactness,
+    /// Fewer changes of building between the lessons of each day
+    FewerBuildingChanges,
+}
+
+/// Two shifts with lessons at the same time
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Clash {
+    pub first: ShiftKey,
+    pub second: ShiftKey,
+    pub weekday: Weekday,
+}
+
+/// A choice of one shift per shift type of each class instance
+#[derive(Debug, Clone)]
+pub struct Timetable {
+    pub shifts: Vec<ClassShift>,
+    /// The cost of this timetable as per each criterion, in their order
+    pub costs: Vec<u32>,
+}
+
+/// Builds timetables without clashes out of the shifts of class instances,
+/// ranked by a set of criteria.
+///
+/// Criteria are applied in the order they are added, with each one only
+/// breaking the ties of those before it.
+#[derive(Debug, Clone, Default)]
+pub struct TimetablePlanner {
+    criteria: Vec<Criterion>,
+    limit: Option<usize>,
+}
+
+/// Shifts to pick one out of, along with their lessons
+type Options = Vec<(ClassShift, Vec<Lesson>)>;
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Lesson {
+    pub(crate) shift: ShiftKey,
+    pub(crate) weekday: Weekday,
+    /// Minutes from midnight
+    pub(crate) start: u32,
+    pub(crate) end: u32,
+    pub(crate) building: Option<BuildingKey>,
+}
+
+impl Lesson {
+    fn overlaps(&self, other: &Lesson) -> bool {
+        self.weekday == other.weekday
+            && self.start < other.end
+            && other.start < self.end
+    }
+}
+
+impl TimetablePlanner {
+    #[must_use]
+    pub fn new() -> TimetablePlanner {
+        TimetablePlanner::default()
+    }
+
+    #[must_use]
+    pub fn rank_by(mut self, criterion: Criterion) -> TimetablePlanner {
+        self.criteria.push(criterion);
+        self
+    }
+
+    /// Keeps only the best `count` timetables
+    #[must_use]
+    pub fn limit(mut self, count: usize) -> TimetablePlanner {
+        self.limit = Some(count);
+        self
+    }
+
+    /// Every timetable without clashes, best first.
+    ///
+    /// Combinations are searched one shift at a time, so the more shifts
+    /// there are to choose from, the longer this takes.
+    /// With a limit, those which can't rank among the best found so far are
+    /// dropped before being complete.
+    pub async fn plan(
+        &self,
+        class_instances: &[ClassInstance],
+    ) -> Result<Vec<Timetable>, Error> {
+        let buildings =
+            if self.criteria.contains(&Criterion::FewerBuildingChanges) {
+                buildings(class_instances).await?
+            } else {
+                HashMap::new()
+            };
+
+        // One shift is to be picked out of each of these
+        let mut choices: BTreeMap<(ClassInstanceKey, ShiftType), Options> =
+            BTreeMap::new();
+        for class_inst in class_instances {
+            for shift in class_inst.get_shifts().await? {
+                let lessons = lessons(&shift, &buildings);
+                choices
+                    .entry((class_inst.id, shift.shift_type))
+                    .or_default()
+                    .push((shift, lessons));
+            }
+        }
+        // Narrower choices first, so that clashes are found earlier
+        let mut choices: Vec<_> = choices.into_values().collect();
+        choices.sort_by_key(Vec::len);
+
+        let mut search = Search {
+            criteria: &self.criteria,
+            limit: self.limit,
+            choices: &choices,
+            picked: vec![],
+            lessons: vec![],
+            found: 0,
+            best: BinaryHeap::new(),
+        };
+        search.descend();
+        Ok(search
+            .best
+            .into_sorted_vec()
+            .into_iter()
+            .map(|ranked| Timetable {
+                shifts: ranked
+                    .picks
+                    .iter()
+                    .map(|(choice, pick)| choices[*choice][*pick].0.clone())
+                    .collect(),
+                costs: ranked.costs,
+            })
+            .collect())
+    }
+}
+
+/// Every pair of shifts with lessons at the same time
+#[must_use]
+pub fn clashes(shifts: &[ClassShift]) -> Vec<Clash> {
+    let lessons: Vec<Lesson> = shifts
+        .iter()
+        .flat_map(|shift| {
+            shift.instances.iter().map(|instance| Lesson {
+                shift: shift.id,
+                weekday: instance.weekday,
+                start: u32::from(instance.start),
+                end: u32::from(instance.start) + u32::from(instance.duration),
+                building: None,
+            })
+        })
+        .collect();
+
+    let mut result = vec![];
+    for (index, lesson) in lessons.iter().enumerate() {
+        for other in &lessons[index + 1..] {
+            if lesson.shift != other.shift && lesson.overlaps(other) {
+                result.push(Clash {
+                    first: lesson.shift,
+                    second: other.shift,
+                    weekday: lesson.weekday,
+                });
+            }
+        }
+    }
+    result
+}
+
+/// The building of every place, if there is a lesson anywhere.
+/// Places are fetched all at once, rather than one per lesson.
+async fn buildings(
+    class_instances: &[ClassInstance],
+) -> Result<HashMap<PlaceKey, BuildingKey>, Error> {
+    let Some(class_inst) = class_instances.first() else {
+        return Ok(HashMap::new());
+    };
+    let client = class_inst.parent.client();
+    Ok(client
+        .get_places(&client.request_config)
+        .await?
+        .into_iter()
+        .filter_map(|place| {
+            Some((place.id, place.building.as_ref()?.identifier))
+        })
+        .collect())
+}
+
+fn lessons(
+    shift: &ClassShift,
+    buildings: &HashMap<PlaceKey, BuildingKey>,
+) -> Vec<Lesson> {
+    shift
+        .instances
+        .iter()
+        .map(|instance| {
+            let start = u32::from(instance.start);
+            Lesson {
+                shift: shift.id,
+                weekday: instance.weekday,
+                start,
+                end: start + u32::from(instance.duration),
+                building: instance.room.as_ref().and_then(|room_ref| {
+                    buildings.get(&room_ref.identifier).copied()
+                }),
+            }
+        })
+        .collect()
+}
+
+/// A combination of picks, ordered by its costs and then by when it was
+/// found
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+struct Ranked {
+    costs: Vec<u32>,
+    order: usize,
+    picks: Vec<(usize, usize)>,
+}
+
+/// Depth-first search of the combinations without clashes, keeping the best
+/// found so far
+struct Search<'a> {
+    criteria: &'a [Criterion],
+    limit: Option<usize>,
+    choices: &'a [Options],
+    /// Pairs of the choice index and the option index
+    picked: Vec<(usize, usize)>,
+    /// Lessons of the picks so far
+    lessons: Vec<Lesson>,
+    found: usize,
+    /// Worst on top, to be dropped once over the limit
+    best: BinaryHeap<Ranked>,
+}
+
+impl Search<'_> {
+    /// Goes through every pick of one option out of each of the remaining
+    /// choices that doesn't clash with those picked so far
+    fn descend(&mut self) {
+        let choice = self.picked.len();
+        let Some(options) = self.choices.get(choice) else {
+            self.keep();
+            return;
+        };
+        if self.is_hopeless() {
+            return;
+        }
+        for (option, (_, lessons)) in options.iter().enumerate() {
+            let clashes = lessons.iter().any(|lesson| {
+                self.lessons.iter().any(|other| lesson.overlaps(other))
+            });
+            if clashes {
+                continue;
+            }
+            let picked_lessons = self.lessons.len();
+            self.picked.push((choice, option));
+            self.lessons.extend_from_slice(lessons);
+            self.descend();
+            self.lessons.truncate(picked_lessons);
+            self.picked.pop();
+        }
+    }
+
+    fn keep(&mut self) {
+        let costs = self
+            .criteria
+            .iter()
+            .map(|criterion| cost(*criterion, &self.lessons))
+            .collect();
+        self.best.push(Ranked {
+            costs,
+            order: self.found,
+            picks: self.picked.clone(),
+        });
+        self.found += 1;
+        if self.limit.is_some_and(|limit| self.best.len() > limit) {
+            self.best.pop();
+        }
+    }
+
+    /// Whether every combination out of the picks so far is certain to
+    /// rank below those kept already
+    fn is_hopeless(&self) -> bool {
+        match self.limit {
+            Some(limit) if self.best.len() >= limit => {
+                let bound: Vec<u32> = self
+                    .criteria
+                    .iter()
+                    .map(|criterion| lower_bound(*criterion, &self.lessons))
+                    .collect();
+                self.best.peek().is_none_or(|worst| bound > worst.costs)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The least cost any timetable with `lessons` can have.
+/// More lessons never lower a cost, save for compactness, as they might
+/// fill in the gaps.
+pub(crate) fn lower_bound(criterion: Criterion, lessons: &[Lesson]) -> u32 {
+    match criterion {
+        Criterion::Compactness => 0,
+        _ => cost(criterion, lessons),
+    }
+}
+
+pub(crate) fn cost(criterion: Criterion, lessons: &[Lesson]) -> u32 {
+    let mut days: HashMap<Weekday, Vec<&Lesson>> = HashMap::new();
+    for lesson in lessons {
+        days.entry(lesson.weekday).or_default().push(lesson);
+    }
+    for day in days.values_mut() {
+        day.sort_by_key(|lesson| lesson.start);
+    }
+
+    match criterion {
+        Criterion::NoEarlyMornings(time) => {
+            let earliest = time.num_seconds_from_midnight() / 60;
+            saturating_count(
+                lessons
+                    .iter()
+                    .filter(|lesson| lesson.start < earliest)
+                    .count(),
+            )
+        }
+        Criterion::FreeDays => saturating_count(days.len()),
+        Criterion::Compactness => days
+            .values()
+            .flat_map(|day| day.windows(2))
+            .map(|pair| pair[1].start.saturating_sub(pair[0].end))
+            .sum(),
+        Criterion::FewerBuildingChanges => days
+            .values()
+            .map(|day| {
+                // Lessons elsewhere or without a place are not changes
+                let buildings: Vec<BuildingKey> =
+                    day.iter().filter_map(|lesson| lesson.building).collect();
+                saturating_count(
+                    buildings
+                        .windows(2)
+                        .filter(|pair| pair[0] != pair[1])
+                        .count(),
+                )
+            })
+            .sum(),
+    }
+}
+
+fn saturating_count(count: usize) -> u32 {
+    u32::try_from(count).unwrap_or(u32::MAX)
+}